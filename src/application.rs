@@ -1,3 +1,5 @@
+pub mod cli;
 pub mod femtovg_init;
 pub mod handler;
 pub mod parser;
+pub mod scene;
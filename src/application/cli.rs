@@ -0,0 +1,354 @@
+use std::fs;
+use std::io::{self, IsTerminal};
+
+use winit::dpi::{LogicalPosition, LogicalSize};
+
+use super::parser;
+use super::scene::{View, DEFAULT_SIZE};
+use crate::demo_equations;
+use crate::grapher::drawing::recording::Recording;
+use crate::grapher::equation::{Calculate, Equation};
+use crate::grapher::graph::Scale;
+
+pub const USAGE: &str = "\
+Usage: graph [COMMAND] [OPTIONS] [EQUATION...]
+
+With no command the equations are drawn in a window.
+
+Commands:
+  eval     print each equation's value at the --at x values, one x per line
+  roots    print the roots in the view's x range, one per line as: equation number, x, multiplicity
+  render   save the graph with -o, --svg or --png without opening a window
+
+Equations are given as arguments, eg: graph \"x^2 - 1\" \"sin(x)/x\", read with --file,
+piped to stdin, or entered at a prompt when there aren't any. Files and stdin have one
+equation per line, anything after a # is a comment.
+
+Options:
+  --file FILE         read equations from FILE, one per line
+  --demo              add the demo equations
+  --size WxH          the view's size in pixels [default: 1000x600]
+  --scale S           pixels per unit [default: 50]
+  --center X,Y        the point in the middle of the view [default: 0,0]
+  --at X,X,...        the x values for eval, eg: --at -1,0,2.5
+  -o, --output FILE   save the graph to FILE, as an svg or png from its extension
+  --svg FILE          save the graph as an svg
+  --png FILE          save the graph as a png
+  --no-window         don't open a window, only save the graph
+  -h, --help          print this
+";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    Graph,
+    Eval,
+    Roots,
+    Render,
+}
+
+// everything asked for on the command line
+#[derive(Debug, PartialEq)]
+pub struct Args {
+    pub command: Command,
+    // unparsed, so they're parsed along with the file
+    pub equations: Vec<String>,
+    pub file: Option<String>,
+    pub demo: bool,
+    pub view: View,
+    pub at: Vec<f32>,
+    pub svg: Option<String>,
+    pub png: Option<String>,
+    pub no_window: bool,
+    pub help: bool,
+}
+
+impl Args {
+    // the arguments after the program name, a command has to come first
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+        let mut args = args.into_iter().peekable();
+
+        let command = match args.peek().map(String::as_str) {
+            Some("eval") => Command::Eval,
+            Some("roots") => Command::Roots,
+            Some("render") => Command::Render,
+            _ => Command::Graph,
+        };
+        if command != Command::Graph {
+            args.next();
+        }
+
+        let mut parsed = Args {
+            command,
+            equations: vec![],
+            file: None,
+            demo: false,
+            view: View::new(DEFAULT_SIZE),
+            at: vec![],
+            svg: None,
+            png: None,
+            no_window: false,
+            help: false,
+        };
+        // the center is in units, so it waits for the scale
+        let mut center = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => parsed.help = true,
+                "--demo" => parsed.demo = true,
+                "--no-window" => parsed.no_window = true,
+                "--file" => parsed.file = Some(value(&mut args, &arg)?),
+                "--svg" => parsed.svg = Some(value(&mut args, &arg)?),
+                "--png" => parsed.png = Some(value(&mut args, &arg)?),
+                "-o" | "--output" => {
+                    let file = value(&mut args, &arg)?;
+                    match file.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()) {
+                        Some(ext) if ext == "svg" => parsed.svg = Some(file),
+                        Some(ext) if ext == "png" => parsed.png = Some(file),
+                        _ => return Err(format!("{arg} should end in .svg or .png, not '{file}'")),
+                    }
+                }
+                "--size" => {
+                    let size = value(&mut args, &arg)?;
+                    let (width, height) = parse_pair(&size, 'x')
+                        .filter(|(w, h)| *w >= 1. && *h >= 1.)
+                        .ok_or(format!("--size should be like 800x600, not '{size}'"))?;
+                    parsed.view.size = LogicalSize::new(width.round(), height.round());
+                }
+                "--scale" => {
+                    let scale = value(&mut args, &arg)?;
+                    let scale: f32 = scale
+                        .parse()
+                        .ok()
+                        .filter(|s: &f32| s.is_finite() && *s > 0.)
+                        .ok_or(format!(
+                            "--scale should be a positive number, not '{scale}'"
+                        ))?;
+                    parsed.view.scale = Scale::uniform(scale);
+                }
+                "--center" => {
+                    let text = value(&mut args, &arg)?;
+                    center = Some(
+                        parse_pair(&text, ',')
+                            .ok_or(format!("--center should be like 2,-1.5, not '{text}'"))?,
+                    );
+                }
+                "--at" => {
+                    let text = value(&mut args, &arg)?;
+                    parsed.at = text
+                        .split(',')
+                        .map(|x| x.trim().parse().ok().filter(|x: &f32| x.is_finite()))
+                        .collect::<Option<_>>()
+                        .ok_or(format!(
+                            "--at should be numbers like -1,0,2.5, not '{text}'"
+                        ))?;
+                }
+                // equations can start with a minus, so only double dashes are options
+                flag if flag.starts_with("--") => {
+                    return Err(format!("unknown option '{flag}', see --help"));
+                }
+                _ => parsed.equations.push(arg),
+            }
+        }
+
+        if let Some((x, y)) = center {
+            // the offset is where the origin is from the middle, in pixels
+            parsed.view.offset =
+                LogicalPosition::new(-x * parsed.view.scale.x, y * parsed.view.scale.y);
+        }
+
+        if parsed.help {
+            return Ok(parsed);
+        }
+        let saves = parsed.svg.is_some() || parsed.png.is_some();
+        match parsed.command {
+            Command::Eval if parsed.at.is_empty() => {
+                Err(String::from("eval needs x values, eg: --at -1,0,2.5"))
+            }
+            Command::Eval | Command::Roots if saves => Err(String::from(
+                "eval and roots only print, use render to save the graph",
+            )),
+            Command::Render if !saves => Err(String::from(
+                "render needs a file to save to, eg: -o graph.png",
+            )),
+            Command::Graph if parsed.no_window && !saves => Err(String::from(
+                "--no-window needs a file to save to, eg: -o graph.png",
+            )),
+            _ => Ok(parsed),
+        }
+    }
+
+    // only graphing without any files to save opens a window
+    pub fn opens_window(&self) -> bool {
+        self.command == Command::Graph
+            && !self.no_window
+            && self.svg.is_none()
+            && self.png.is_none()
+    }
+
+    // the demo equations, then the arguments, then the file's
+    // with none of those they're read from stdin when it's piped in, eg: echo "x^2 - 1" | graph
+    // only empty when stdin is a terminal and there's no file, so they can be asked for
+    pub fn read_equations(&self) -> Result<Vec<Equation>, String> {
+        let mut equations = if self.demo { demo_equations() } else { vec![] };
+
+        let mut errors = vec![];
+        for (i, input) in self.equations.iter().enumerate() {
+            match parser::parse_expression(input) {
+                Ok(equation) => equations.push(equation),
+                Err(e) => errors.push(format!("argument {}: {e}", i + 1)),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors.join("\n"));
+        }
+
+        if let Some(file) = &self.file {
+            let text =
+                fs::read_to_string(file).map_err(|e| format!("Couldn't read {file}: {e}"))?;
+            equations.extend(parse_text(file, &text)?);
+        } else if equations.is_empty() && !io::stdin().is_terminal() {
+            let text =
+                io::read_to_string(io::stdin()).map_err(|e| format!("Couldn't read stdin: {e}"))?;
+            equations = parse_text("stdin", &text)?;
+        }
+
+        // a file or pipe with nothing in it can't be answered at a prompt
+        let prompt = self.file.is_none() && io::stdin().is_terminal();
+        if equations.is_empty() && !prompt {
+            return Err(String::from("No equations were given, see --help"));
+        }
+
+        Ok(equations)
+    }
+}
+
+fn parse_text(source: &str, text: &str) -> Result<Vec<Equation>, String> {
+    parser::parse_lines(text).map_err(|errors| {
+        let count = match errors.len() {
+            1 => String::from("1 line"),
+            n => format!("{n} lines"),
+        };
+        let described = parser::describe_line_errors(source, &errors);
+        format!("{described}\n{count} of {source} couldn't be parsed")
+    })
+}
+
+fn value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or(format!("{flag} needs a value, see --help"))
+}
+
+// two finite numbers with a separator between them, eg: 800x600
+fn parse_pair(text: &str, separator: char) -> Option<(f32, f32)> {
+    let (a, b) = text.split_once(separator)?;
+    let (a, b): (f32, f32) = (a.trim().parse().ok()?, b.trim().parse().ok()?);
+
+    (a.is_finite() && b.is_finite()).then_some((a, b))
+}
+
+// a line for each x, with the x then each equation's value, separated by tabs
+pub fn eval_table(equations: &[Equation], at: &[f32]) -> String {
+    at.iter()
+        .map(|&x| {
+            let values = equations.iter().map(|e| format!("\t{}", e.calc(x)));
+            format!("{x}{}\n", values.collect::<String>())
+        })
+        .collect()
+}
+
+// a line for each root with the equation's number from 1, the x and its multiplicity, separated by tabs
+// numeric roots of equations that aren't polynomials always have a multiplicity of 1
+pub fn roots_table(equations: &[Equation], view: View) -> String {
+    let mut recording = Recording::new();
+    let (min_x, max_x) = view.graph(&mut recording).get_x_range();
+
+    let mut table = String::new();
+    for (i, e) in equations.iter().enumerate() {
+        for root in e.roots_in(min_x, max_x) {
+            table += &format!("{}\t{}\t{}\n", i + 1, root.x, root.multiplicity);
+        }
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn options_and_equations() {
+        let args = parse(&[
+            "-x^2", "--scale", "20", "--center", "1,-2", "--svg", "a.svg",
+        ])
+        .unwrap();
+        assert_eq!(args.command, Command::Graph);
+        assert_eq!(args.equations, vec!["-x^2"]);
+        assert_eq!(args.view.scale, Scale::uniform(20.));
+        // the center is scaled however it's ordered with --scale
+        assert_eq!(args.view.offset, LogicalPosition::new(-20., -40.));
+        assert!(!args.opens_window());
+
+        let args = parse(&["render", "x", "-o", "out.PNG", "--size", "300x200"]).unwrap();
+        assert_eq!(args.command, Command::Render);
+        assert_eq!(args.png.as_deref(), Some("out.PNG"));
+        assert_eq!(args.view.size, LogicalSize::new(300., 200.));
+    }
+
+    #[test]
+    fn bad_arguments_are_errors() {
+        assert!(parse(&["--size", "0x5"]).is_err());
+        assert!(parse(&["--scale"]).is_err());
+        assert!(parse(&["--frobnicate"]).is_err());
+        assert!(parse(&["eval", "x"]).is_err());
+        assert!(parse(&["eval", "x", "--at", "1,two"]).is_err());
+        assert!(parse(&["render", "x"]).is_err());
+        assert!(parse(&["render", "-o", "graph.jpg"]).is_err());
+        assert!(parse(&["render", "--help"]).is_ok());
+        // options that would otherwise do nothing
+        assert!(parse(&["x^2", "--no-window"]).is_err());
+        assert!(parse(&["x^2", "--no-window", "--png", "a.png"]).is_ok());
+        assert!(parse(&["eval", "x", "--at", "1", "--svg", "out.svg"]).is_err());
+        assert!(parse(&["roots", "x", "-o", "out.png"]).is_err());
+    }
+
+    #[test]
+    fn pairs_of_numbers() {
+        assert_eq!(parse_pair("800x600", 'x'), Some((800., 600.)));
+        assert_eq!(parse_pair("-2.5, 1", ','), Some((-2.5, 1.)));
+        assert_eq!(parse_pair("800", 'x'), None);
+        assert_eq!(parse_pair("1,nan", ','), None);
+    }
+
+    #[test]
+    fn eval_and_roots_print_tables() {
+        let args = parse(&["roots", "x^2 - 4", "x + 20", "(x - 1)^2"]).unwrap();
+        let equations = args.read_equations().unwrap();
+
+        assert_eq!(
+            eval_table(&equations, &[0., 2.5]),
+            "0\t-4\t20\t1\n2.5\t2.25\t22.5\t2.25\n"
+        );
+        // -20 is off the default view
+        assert_eq!(
+            roots_table(&equations, args.view),
+            "1\t-2\t1\n1\t2\t1\n3\t1\t2\n"
+        );
+    }
+
+    #[test]
+    fn files_without_equations_are_errors() {
+        let file = std::env::temp_dir().join(format!("graph-comments-{}.txt", std::process::id()));
+        fs::write(&file, "# just a comment\n\n").unwrap();
+
+        let args = parse(&["roots", "--file", file.to_str().unwrap()]).unwrap();
+        let result = args.read_equations();
+        fs::remove_file(&file).unwrap();
+        assert_eq!(result.unwrap_err(), "No equations were given, see --help");
+    }
+}
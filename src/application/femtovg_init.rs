@@ -11,7 +11,8 @@ mod non_wasm_imports {
     #[allow(deprecated)]
     pub use raw_window_handle::HasRawWindowHandle;
     pub use std::num::NonZeroU32;
-    pub use winit::{dpi::PhysicalSize, window::WindowAttributes};
+    pub use winit::dpi::LogicalSize;
+    pub use winit::window::WindowAttributes;
 }
 #[cfg(not(target_arch = "wasm32"))]
 use non_wasm_imports::*;
@@ -22,38 +23,42 @@ use winit::event_loop::EventLoop;
 use winit::window::Window;
 
 use super::handler::MyApplicationHandler;
-use crate::grapher::equation::Polynomial;
+use super::scene::View;
+use crate::grapher::drawing;
+use crate::grapher::equation::Equation;
 
+// the window starts at the view's size, on the web the canvas element's size is used instead
 pub fn init_canvas<T>(
     event_loop: &EventLoop<T>,
-    equations: Vec<Polynomial>,
+    view: View,
+    equations: Vec<Equation>,
 ) -> MyApplicationHandler {
     #[cfg(not(target_arch = "wasm32"))]
-    let (current_context, canvas, window, surface) = init_native(event_loop);
+    let (current_context, mut canvas, window, surface) = init_native(event_loop, view.size);
 
     #[cfg(target_arch = "wasm32")]
-    let (canvas, window) = init_wasm(event_loop);
+    let (mut canvas, window) = init_wasm(event_loop);
 
-    let default_scale = 50.;
+    // any loaded font is used for text, so the id isn't needed
+    canvas.add_font_mem(drawing::FONT).expect("Cannot add font");
 
     window.focus_window();
-    let app = MyApplicationHandler::new(
+    MyApplicationHandler::new(
         window,
         #[cfg(not(target_arch = "wasm32"))]
         current_context,
         #[cfg(not(target_arch = "wasm32"))]
         surface,
         canvas,
-        default_scale,
+        view,
         equations,
-    );
-
-    app
+    )
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 fn init_native<T>(
     event_loop: &EventLoop<T>,
+    size: LogicalSize<f32>,
 ) -> (
     PossiblyCurrentContext,
     Canvas<OpenGl>,
@@ -63,7 +68,7 @@ fn init_native<T>(
     let template = ConfigTemplateBuilder::new().with_alpha_size(8);
 
     let window_attr = WindowAttributes::default()
-        .with_inner_size(PhysicalSize::new(1000., 600.))
+        .with_inner_size(size)
         .with_title("graph_rs");
     let display_builder = DisplayBuilder::new().with_window_attributes(Some(window_attr));
 
@@ -88,13 +93,15 @@ fn init_native<T>(
             .unwrap()
     });
 
+    // physical pixels, more than the logical size asked for on high dpi screens
+    let size = window.inner_size();
     #[allow(deprecated)]
     let attrs = SurfaceAttributesBuilder::<WindowSurface>::new().build(
         window
             .raw_window_handle()
             .expect("raw window handle failed"),
-        NonZeroU32::new(1000).unwrap(),
-        NonZeroU32::new(600).unwrap(),
+        NonZeroU32::new(size.width).unwrap(),
+        NonZeroU32::new(size.height).unwrap(),
     );
 
     let surface = unsafe {
@@ -115,7 +122,7 @@ fn init_native<T>(
             .expect("Cannot create renderer");
 
     let mut canvas = Canvas::new(renderer).expect("Cannot create canvas");
-    canvas.set_size(1000, 600, window.scale_factor() as f32);
+    canvas.set_size(size.width, size.height, 1.);
 
     (current_context, canvas, window, surface)
 }
@@ -1,5 +1,7 @@
 #[cfg(not(target_arch = "wasm32"))]
 mod non_wasm_imports {
+    pub use std::num::NonZeroU32;
+
     pub use glutin::{
         context::PossiblyCurrentContext,
         prelude::*,
@@ -12,29 +14,56 @@ use non_wasm_imports::*;
 use femtovg::renderer::OpenGl;
 use femtovg::{Canvas, Color};
 use winit::application::ApplicationHandler;
-use winit::dpi::PhysicalPosition;
+use winit::dpi::{LogicalPosition, LogicalSize};
 use winit::event::{ElementState, MouseScrollDelta, WindowEvent};
 use winit::event_loop::ActiveEventLoop;
-use winit::keyboard::{Key, NamedKey};
+use winit::keyboard::{Key, ModifiersState, NamedKey};
 use winit::window::Window;
 use winit::window::WindowId;
 
-use crate::grapher::equation::Polynomial;
-use crate::grapher::graph::Graph;
+use super::scene::{self, Overlays, Plot, View};
+use crate::grapher::drawing::recording::Recording;
+use crate::grapher::equation::{Equation, Point};
+use crate::grapher::graph::{self, AxisModes, Scale, DEFAULT_SCALE};
+
+// how much one line of the scroll wheel zooms, as the log of the factor
+const ZOOM_PER_LINE: f32 = 0.2;
+// trackpads scroll in pixels, about this many logical pixels make a line
+const PIXELS_PER_LINE: f32 = 20.;
+
+// how far the arrow keys and wasd move the graph
+const PAN_STEP_PX: f32 = 40.;
+// how much + and - zoom
+const KEY_ZOOM_FACTOR: f32 = 1.25;
+
+// highest derivative that can be overlaid, f' and f''
+const MAX_DERIVATIVE_ORDER: u32 = 2;
+
+// pixels per unit limits, past these f32 runs out of precision for the conversions
+const MIN_SCALE: f32 = 1e-3;
+const MAX_SCALE: f32 = 1e5;
 
 pub struct MyApplicationHandler {
     close_requested: bool,
-    scale: f32,
+    scale: Scale,
+    // keeps the x and y scales the same so circles look like circles
+    equal_aspect: bool,
+    axes: AxisModes,
     dragging: bool,
-    previous_position: Option<PhysicalPosition<f32>>,
-    offset: PhysicalPosition<f32>,
+    selecting_area: bool,
+    modifiers: ModifiersState,
+    // everything in pixels is logical, so it's the same on high dpi screens
+    cursor_position: Option<LogicalPosition<f32>>,
+    previous_position: Option<LogicalPosition<f32>>,
+    offset: LogicalPosition<f32>,
+    overlays: Overlays,
     window: Window,
     #[cfg(not(target_arch = "wasm32"))]
     context: PossiblyCurrentContext,
     #[cfg(not(target_arch = "wasm32"))]
     surface: Surface<WindowSurface>,
     canvas: Canvas<OpenGl>,
-    equations: Vec<Polynomial>,
+    plots: Vec<Plot>,
 }
 
 impl MyApplicationHandler {
@@ -43,10 +72,9 @@ impl MyApplicationHandler {
         #[cfg(not(target_arch = "wasm32"))] context: PossiblyCurrentContext,
         #[cfg(not(target_arch = "wasm32"))] surface: Surface<WindowSurface>,
         canvas: Canvas<OpenGl>,
-        scale: f32,
-        equations: Vec<Polynomial>,
+        view: View,
+        equations: Vec<Equation>,
     ) -> Self {
-        let def_position = PhysicalPosition::<f32>::default();
         MyApplicationHandler {
             window,
             #[cfg(not(target_arch = "wasm32"))]
@@ -54,14 +82,148 @@ impl MyApplicationHandler {
             #[cfg(not(target_arch = "wasm32"))]
             surface,
             canvas,
-            equations,
-            offset: def_position,
+            plots: scene::plots(equations),
+            offset: view.offset,
+            overlays: Overlays::default(),
             previous_position: None,
+            cursor_position: None,
+            modifiers: ModifiersState::default(),
+            selecting_area: false,
             dragging: false,
-            scale,
+            scale: view.scale,
+            equal_aspect: false,
+            axes: view.axes,
             close_requested: false,
         }
     }
+
+    // math coordinates of the cursor, using the same conversion as the graph
+    // nothing is drawn, so the graph is on a recording instead of the canvas
+    fn cursor_point(&self) -> Option<Point> {
+        let cursor = self.cursor_position?;
+        let mut recording = Recording::new();
+        let graph = self.view().graph(&mut recording);
+
+        Some(graph.convert_px_to_point((cursor.x, cursor.y)))
+    }
+
+    // zooms each axis by its factor, keeping the point under the anchor in place
+    // the offset is the origin's distance from the center in pixels, so it's scaled about the anchor
+    fn zoom(&mut self, x_factor: f32, y_factor: f32, anchor: LogicalPosition<f32>) {
+        let new_scale = Scale {
+            x: (self.scale.x * x_factor).clamp(MIN_SCALE, MAX_SCALE),
+            y: (self.scale.y * y_factor).clamp(MIN_SCALE, MAX_SCALE),
+        };
+
+        let center = self.screen_center();
+        self.offset = LogicalPosition::new(
+            zoomed_offset(
+                self.offset.x,
+                anchor.x - center.x,
+                new_scale.x / self.scale.x,
+            ),
+            zoomed_offset(
+                self.offset.y,
+                anchor.y - center.y,
+                new_scale.y / self.scale.y,
+            ),
+        );
+        self.scale = new_scale;
+        self.window.request_redraw();
+    }
+
+    fn pan(&mut self, delta_x: f32, delta_y: f32) {
+        self.offset = LogicalPosition::new(self.offset.x + delta_x, self.offset.y + delta_y);
+        self.window.request_redraw();
+    }
+
+    // back to the default scale with the origin in the middle
+    fn reset_view(&mut self) {
+        self.scale = Scale::uniform(DEFAULT_SCALE);
+        self.offset = LogicalPosition::default();
+        self.window.request_redraw();
+    }
+
+    // zooms and pans so the roots, extrema and intercepts of every equation are on screen
+    // equations with endlessly many, eg: sin(x), only have the ones on screen now counted
+    fn fit_features(&mut self) {
+        let view = self.view();
+        let mut recording = Recording::new();
+        let (min_x, max_x) = view.graph(&mut recording).get_x_range();
+
+        let features: Vec<Point> = self
+            .plots
+            .iter()
+            .flat_map(|plot| plot.equation.features(min_x, max_x))
+            .collect();
+
+        if let Some((scale, offset)) =
+            graph::fit_view(&features, view.size, self.axes, self.equal_aspect)
+        {
+            self.scale = Scale {
+                x: scale.x.clamp(MIN_SCALE, MAX_SCALE),
+                y: scale.y.clamp(MIN_SCALE, MAX_SCALE),
+            };
+            // the offset is in pixels, so it follows the clamped scale
+            self.offset = LogicalPosition::new(
+                offset.x * self.scale.x / scale.x,
+                offset.y * self.scale.y / scale.y,
+            );
+            self.window.request_redraw();
+        }
+    }
+
+    fn screen_center(&self) -> LogicalPosition<f32> {
+        let size = self.logical_size();
+        LogicalPosition::new(size.width / 2., size.height / 2.)
+    }
+
+    fn logical_size(&self) -> LogicalSize<f32> {
+        self.window
+            .inner_size()
+            .to_logical(self.window.scale_factor())
+    }
+
+    fn view(&self) -> View {
+        View {
+            size: self.logical_size(),
+            scale: self.scale,
+            offset: self.offset,
+            axes: self.axes,
+        }
+    }
+
+    // writes what's on screen to the next free graph.svg in the working directory
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_svg(&self) {
+        let svg = scene::to_svg(self.view(), &self.plots, self.overlays);
+        let path = scene::unused_path("graph", "svg");
+        match std::fs::write(&path, svg) {
+            Ok(()) => println!("Saved {}", path.display()),
+            Err(error) => eprintln!("Couldn't save {}: {}", path.display(), error),
+        }
+    }
+
+    fn render(&mut self) {
+        let view = self.view();
+        render_canvas(
+            &self.window,
+            &mut self.canvas,
+            view,
+            &self.plots,
+            self.overlays,
+        ); // this part doesn't change
+
+        // Tell renderer to execute all drawing commands
+        self.canvas.flush_to_surface(&());
+        // wgpu would use a texture here
+
+        // Display what we've just rendered
+        #[cfg(not(target_arch = "wasm32"))]
+        self.surface
+            .swap_buffers(&self.context)
+            .expect("Could not swap buffers");
+    }
 }
 
 impl ApplicationHandler for MyApplicationHandler {
@@ -82,91 +244,192 @@ impl ApplicationHandler for MyApplicationHandler {
             WindowEvent::KeyboardInput { event, .. } => {
                 let key = event.logical_key;
 
-                match key {
+                if event.state != ElementState::Pressed {
+                    return;
+                }
+
+                match key.as_ref() {
                     Key::Named(NamedKey::Escape) => {
                         self.close_requested = true;
                     }
+                    // arrows and wasd move the view, so the graph moves the other way
+                    // d used to toggle the derivatives, they're on p now
+                    Key::Named(NamedKey::ArrowLeft) | Key::Character("a") => {
+                        self.pan(PAN_STEP_PX, 0.);
+                    }
+                    Key::Named(NamedKey::ArrowRight) | Key::Character("d") => {
+                        self.pan(-PAN_STEP_PX, 0.);
+                    }
+                    Key::Named(NamedKey::ArrowUp) | Key::Character("w") => {
+                        self.pan(0., PAN_STEP_PX);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    Key::Character("s") if self.modifiers.control_key() => {
+                        self.export_svg();
+                    }
+                    Key::Named(NamedKey::ArrowDown) | Key::Character("s") => {
+                        self.pan(0., -PAN_STEP_PX);
+                    }
+                    // = is + without shift
+                    Key::Character("+") | Key::Character("=") => {
+                        self.zoom(KEY_ZOOM_FACTOR, KEY_ZOOM_FACTOR, self.screen_center());
+                    }
+                    Key::Character("-") => {
+                        let factor = 1. / KEY_ZOOM_FACTOR;
+                        self.zoom(factor, factor, self.screen_center());
+                    }
+                    Key::Character("0") => {
+                        self.reset_view();
+                    }
+                    Key::Character("f") => {
+                        self.fit_features();
+                    }
+                    // cycle through no derivatives, f', then f' and f''
+                    // p for prime, d was taken by panning right
+                    Key::Character("p") => {
+                        self.overlays.derivative_order =
+                            (self.overlays.derivative_order + 1) % (MAX_DERIVATIVE_ORDER + 1);
+                        self.window.request_redraw();
+                    }
+                    Key::Character("e") => {
+                        self.overlays.critical_points = !self.overlays.critical_points;
+                        self.window.request_redraw();
+                    }
+                    // cycles linear, log y, log x and log-log axes
+                    Key::Character("g") => {
+                        self.axes = self.axes.next();
+                        self.window.request_redraw();
+                    }
+                    Key::Character("i") => {
+                        self.overlays.intersections = !self.overlays.intersections;
+                        self.window.request_redraw();
+                    }
+                    // snaps the y scale to the x scale and keeps them together
+                    Key::Character("l") => {
+                        self.equal_aspect = !self.equal_aspect;
+                        if self.equal_aspect {
+                            self.zoom(1., self.scale.x / self.scale.y, self.screen_center());
+                        }
+                    }
+                    Key::Character("r") => {
+                        self.overlays.roots = !self.overlays.roots;
+                        self.window.request_redraw();
+                    }
                     _ => (),
                 }
             }
-            // make it zoom to the center of the screen
-            // each scale increment means one more pixel per unit away from 0,0
-            // (scale_change * units from target to 0,0) = offset change
-            // (scale_change * offset / current scale) = offset change
+            // zooms toward the cursor, so the point under it stays put
+            // ctrl zooms only the x axis and alt only the y axis, unless the aspect is locked
             WindowEvent::MouseWheel { delta, .. } => {
-                // println!("{:?}", delta);
-                let scale_increment = match delta {
-                    MouseScrollDelta::LineDelta(_x_delta, y_delta) => {
-                        let scale_increment = y_delta * 0.2; // adjust zoom speed
-                        scale_increment
-                    }
+                let lines = match delta {
+                    MouseScrollDelta::LineDelta(_x_delta, y_delta) => y_delta,
+                    // physical pixels, made logical so a trackpad zooms as fast on high dpi screens
                     MouseScrollDelta::PixelDelta(delta_position) => {
-                        let scale_increment = (delta_position.y * 0.01) as f32;
-                        scale_increment
+                        let delta = delta_position.to_logical::<f32>(self.window.scale_factor());
+                        delta.y / PIXELS_PER_LINE
                     }
                 };
                 // log and exp so that the zoom speed feels the same when large and small
-                let new_scale = (self.scale.ln() + scale_increment).exp();
-
-                if new_scale > 1. {
-                    let scale_change = new_scale - self.scale;
-                    let offset_change_x = scale_change * self.offset.x / self.scale;
-                    let offset_change_y = scale_change * self.offset.y / self.scale;
-
-                    self.offset = PhysicalPosition::new(
-                        self.offset.x + offset_change_x,
-                        self.offset.y + offset_change_y,
-                    );
-                    self.scale = new_scale;
-                    self.window.request_redraw();
+                let factor = (lines * ZOOM_PER_LINE).exp();
+                let anchor = self.cursor_position.unwrap_or(self.screen_center());
+
+                if self.equal_aspect {
+                    self.zoom(factor, factor, anchor);
+                } else if self.modifiers.control_key() {
+                    self.zoom(factor, 1., anchor);
+                } else if self.modifiers.alt_key() {
+                    self.zoom(1., factor, anchor);
+                } else {
+                    self.zoom(factor, factor, anchor);
                 }
             }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
+            }
             WindowEvent::MouseInput { state, .. } => match state {
                 ElementState::Pressed => {
-                    self.dragging = true;
+                    // shift + drag selects the area to shade instead of panning
+                    let start = self.cursor_point().filter(|_| self.modifiers.shift_key());
+                    match start {
+                        Some(start) => {
+                            self.selecting_area = true;
+                            self.overlays.area = Some((start.x, start.x));
+                            self.window.request_redraw();
+                        }
+                        None => {
+                            self.dragging = true;
+                        }
+                    }
                 }
                 ElementState::Released => {
+                    // shift + click without dragging clears the area
+                    if let Some((a, b)) = self.overlays.area {
+                        if self.selecting_area && a == b {
+                            self.overlays.area = None;
+                            self.window.request_redraw();
+                        }
+                    }
+                    self.selecting_area = false;
                     self.dragging = false;
                     self.previous_position = None;
                 }
             },
-            // now: center stays same position
-            // therefore the cursor moves towards the center when zooming in, away when zooming out
-            // move the center away from the cursor on a line?
             WindowEvent::CursorMoved { position, .. } => {
+                let new_position = position.to_logical::<f32>(self.window.scale_factor());
+                self.cursor_position = Some(new_position);
+                // the crosshair is hidden while dragging the graph
+                self.overlays.crosshair = (!self.dragging).then_some(new_position);
+
+                if self.selecting_area {
+                    if let (Some((a, _)), Some(end)) = (self.overlays.area, self.cursor_point()) {
+                        self.overlays.area = Some((a, end.x));
+                        self.window.request_redraw();
+                    }
+                    return;
+                }
+
                 if !self.dragging {
+                    self.window.request_redraw();
                     return;
                 }
 
-                let new_position = position.cast::<f32>();
                 match self.previous_position {
                     None => {
                         self.previous_position = Some(new_position);
                     }
                     Some(previous_position) => {
-                        let delta_x = new_position.x - previous_position.x;
-                        let delta_y = new_position.y - previous_position.y;
-
-                        self.offset =
-                            PhysicalPosition::new(self.offset.x + delta_x, self.offset.y + delta_y);
-
+                        self.pan(
+                            new_position.x - previous_position.x,
+                            new_position.y - previous_position.y,
+                        );
                         self.previous_position = Some(new_position);
-                        self.window.request_redraw();
                     }
                 }
             }
+            WindowEvent::CursorLeft { .. } => {
+                self.cursor_position = None;
+                self.overlays.crosshair = None;
+                self.window.request_redraw();
+            }
+            // the gl surface doesn't follow the window, it has to be resized to match
+            // minimizing can make the size 0, which a surface can't be
+            WindowEvent::Resized(size) => {
+                #[cfg(not(target_arch = "wasm32"))]
+                if let (Some(width), Some(height)) =
+                    (NonZeroU32::new(size.width), NonZeroU32::new(size.height))
+                {
+                    self.surface.resize(&self.context, width, height);
+                }
+                #[cfg(target_arch = "wasm32")]
+                let _ = size;
+                self.window.request_redraw();
+            }
+            // moved to a screen with a different dpi, a Resized event follows with the new size
+            WindowEvent::ScaleFactorChanged { .. } => {
+                self.window.request_redraw();
+            }
             WindowEvent::RedrawRequested => {
-                render(
-                    #[cfg(not(target_arch = "wasm32"))]
-                    &self.context,
-                    #[cfg(not(target_arch = "wasm32"))]
-                    &self.surface,
-                    &self.window,
-                    &mut self.canvas,
-                    self.scale,
-                    self.offset,
-                    &self.equations,
-                );
+                self.render();
             }
             // _ => println!("{:?}", event),
             _ => {}
@@ -183,43 +446,43 @@ impl ApplicationHandler for MyApplicationHandler {
 fn render_canvas(
     window: &Window,
     canvas: &mut Canvas<OpenGl>,
-    scale: f32,
-    offset: PhysicalPosition<f32>,
-    equations: &Vec<Polynomial>,
+    view: View,
+    plots: &[Plot],
+    overlays: Overlays,
 ) {
     // Make sure the canvas has the right size:
+    // femtovg draws in the units of the size it's given, so it gets the physical size at a dpi of 1
+    // and everything is scaled up from logical pixels with the transform, keeping lines and text sharp
     let size = window.inner_size();
-    canvas.set_size(size.width, size.height, window.scale_factor() as f32);
+    let scale_factor = window.scale_factor();
+    canvas.set_size(size.width, size.height, 1.);
 
     // clear canvas by filling with black
     canvas.clear_rect(0, 0, size.width, size.height, Color::black());
 
-    let mut graph1 = Graph::new(size, scale, offset, canvas);
-    graph1.init_graph();
+    canvas.reset_transform();
+    canvas.scale(scale_factor as f32, scale_factor as f32);
 
-    for equation in equations {
-        graph1.graph_equation(equation);
-    }
+    scene::draw_scene(canvas, view, plots, overlays);
 }
 
-fn render(
-    #[cfg(not(target_arch = "wasm32"))] context: &PossiblyCurrentContext,
-    #[cfg(not(target_arch = "wasm32"))] surface: &Surface<WindowSurface>,
-    window: &Window,
-    canvas: &mut Canvas<OpenGl>,
-    scale: f32,
-    offset: PhysicalPosition<f32>,
-    equations: &Vec<Polynomial>,
-) {
-    render_canvas(window, canvas, scale, offset, equations); // this part doesn't change
+// the offset after zooming by factor about the anchor, both in pixels from the screen center
+// the anchor stays over the same point, so its distance to the origin scales with the zoom
+fn zoomed_offset(offset: f32, anchor: f32, factor: f32) -> f32 {
+    anchor + (offset - anchor) * factor
+}
 
-    // Tell renderer to execute all drawing commands
-    canvas.flush_to_surface(&());
-    // wgpu would use a texture here
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // Display what we've just rendered
-    #[cfg(not(target_arch = "wasm32"))]
-    surface
-        .swap_buffers(context)
-        .expect("Could not swap buffers");
+    #[test]
+    fn zooming_keeps_the_anchor_in_place() {
+        // the origin 100px right of the anchor at 2x is 200px right of it
+        assert_eq!(zoomed_offset(150., 50., 2.), 250.);
+        // about the center it just scales
+        assert_eq!(zoomed_offset(-40., 0., 0.5), -20.);
+        // the origin under the anchor stays there
+        assert_eq!(zoomed_offset(30., 30., 3.), 30.);
+    }
 }
@@ -1,28 +1,27 @@
-use regex::Regex;
-use std::{env, io};
+use std::io;
 
 use crate::{
     demo_equations,
-    grapher::equation::{Polynomial, Term},
+    grapher::equation::{Equation, Polynomial},
 };
 
-pub fn has_demo_arg() -> bool {
-    let args: Vec<String> = env::args().collect();
+mod error;
+mod grammar;
+mod tokenizer;
 
-    args.len() >= 2 && &args[1] == "--demo"
-}
+pub use error::ParseError;
 
-pub fn get_input() -> Vec<Polynomial> {
+// empty if input ends before any equations are entered, eg: ctrl + d at the first prompt
+pub fn get_input() -> Vec<Equation> {
     let demo_or_custom = "[d] Graph a set of demo equations\n[e] Enter custom equations";
     println!("{demo_or_custom}");
 
     let mut demo_or_custom_loop = true;
 
     while demo_or_custom_loop {
-        let mut demo_or_custom_input = String::new();
-        io::stdin()
-            .read_line(&mut demo_or_custom_input)
-            .expect("Failed to read line");
+        let Some(demo_or_custom_input) = read_line() else {
+            return vec![];
+        };
         match demo_or_custom_input.chars().next() {
             Some('d') => {
                 return demo_equations();
@@ -40,12 +39,15 @@ pub fn get_input() -> Vec<Polynomial> {
     get_custom_equations()
 }
 
-fn get_custom_equations() -> Vec<Polynomial> {
+fn get_custom_equations() -> Vec<Equation> {
     let mut enter_another_equation = true;
-    let mut equations: Vec<Polynomial> = Vec::new();
+    let mut equations: Vec<Equation> = Vec::new();
 
     while enter_another_equation {
-        let eq = input_equation();
+        // once input ends, whatever was entered is graphed
+        let Some(eq) = input_equation() else {
+            break;
+        };
         equations.push(eq);
 
         println!("\nEntered equations:");
@@ -59,11 +61,9 @@ fn get_custom_equations() -> Vec<Polynomial> {
 
         print!("{}\n{}\n", eq_prompt, graph_prompt);
 
-        let mut input = String::new();
-
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Failed to read line");
+        let Some(input) = read_line() else {
+            break;
+        };
 
         let first_char = input.chars().next();
 
@@ -84,82 +84,90 @@ fn get_custom_equations() -> Vec<Polynomial> {
     equations
 }
 
-fn input_equation() -> Polynomial {
-    let prompt = "Enter polynomial in the form: 4.2x^2 - 2x + 0.4 (whitespace ignored, exponents must be integers)";
-    println!("{prompt}");
-    let mut input = String::new();
+// keeps prompting until the input parses, printing where the mistake is
+// none once input ends
+fn input_equation() -> Option<Equation> {
+    let prompt = "Enter an equation in x, eg: 4.2x^2 - 2x + 0.4, 2(x+1)^3 - x(x-4) or sin(x)/x (whitespace ignored)";
+
+    loop {
+        println!("{prompt}");
+        let input = read_line()?;
+
+        match parse_expression(&input) {
+            Ok(equation) => {
+                println!("Parsed equation: {}", equation);
+                return Some(equation);
+            }
+            Err(e) => {
+                println!("{e}\nTry again.");
+            }
+        }
+    }
+}
 
-    io::stdin()
+// a line from stdin, none at the end of input so a prompt isn't repeated forever
+fn read_line() -> Option<String> {
+    let mut input = String::new();
+    let read = io::stdin()
         .read_line(&mut input)
         .expect("Failed to read line");
 
-    parse_equation(input)
+    (read > 0).then_some(input)
 }
 
-fn parse_equation(equation_string: String) -> Polynomial {
-    let polystring: String = equation_string.split_whitespace().collect();
+// parses input that must expand to a polynomial
+pub fn parse_equation(equation_string: &str) -> Result<Polynomial, ParseError> {
+    grammar::parse_polynomial(equation_string)
+}
 
-    let regex = Regex::new(r"([+-]?(?:\d+(?:\.\d+)?)?)(x\^?(\d+)?)?").unwrap();
-    // leads to an empty match at the very end
-    let mut terms = Vec::new();
+// parses any equation in x, falling back to a general expression when it isn't a polynomial
+// a syntax error is reported the same way by either parse
+pub fn parse_expression(equation_string: &str) -> Result<Equation, ParseError> {
+    match parse_equation(equation_string) {
+        Ok(poly) => Ok(poly.into()),
+        Err(_) => grammar::parse_expression(equation_string).map(Equation::from),
+    }
+}
 
-    for cap in regex.captures_iter(&polystring) {
-        // println!("{:?}", cap);
-        let whole_term_opt = cap.get(0); // eg, -4.2x^2
-        let coeff_opt = cap.get(1); // eg, -4.2
-        let x_exponential_opt = cap.get(2); // eg, x^2
-        let power_opt = cap.get(3); // eg, 2
+// one equation per line, anything after a # is a comment and blank lines are skipped
+// every line that doesn't parse is an error, numbered from 1
+pub fn parse_lines(text: &str) -> Result<Vec<Equation>, Vec<(usize, ParseError)>> {
+    let mut equations = vec![];
+    let mut errors = vec![];
 
-        let whole_term = whole_term_opt.unwrap().as_str();
-        if whole_term.is_empty() {
+    for (i, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default();
+        if line.trim().is_empty() {
             continue;
         }
 
-        let coeff = match coeff_opt {
-            None => 1.,
-            Some(coeff_match) => match coeff_match.as_str() {
-                "-" => -1.,
-                "+" => 1.,
-                coeff_str => coeff_str.parse::<f32>().expect(&format!(
-                    "invalid coefficient {}, in term: {}",
-                    coeff_str, whole_term
-                )),
-            },
-        };
-
-        let p = match power_opt {
-            None => match x_exponential_opt {
-                None => 0,
-                Some(x_exp_match) => {
-                    if x_exp_match.as_str() == "x" {
-                        1
-                    } else {
-                        panic!("invalid term, {}", whole_term);
-                    }
-                }
-            },
-            Some(power_match) => {
-                let pow_string = power_match.as_str();
-                pow_string.parse::<i32>().expect(&format!(
-                    "invalid power: {}, in term: {}",
-                    pow_string, whole_term
-                ))
-            }
-        };
+        match parse_expression(line) {
+            Ok(equation) => equations.push(equation),
+            Err(e) => errors.push((i + 1, e)),
+        }
+    }
 
-        terms.push(Term::new(coeff, p));
+    if errors.is_empty() {
+        Ok(equations)
+    } else {
+        Err(errors)
     }
-    // println!("Parsed terms: {:?}", terms);
+}
 
-    let poly = Polynomial::new(terms);
-    println!("Parsed equation: {}", poly);
+// all the errors from a file or stdin, each like: equations.txt:3: unknown name 'q'
+pub fn describe_line_errors(source: &str, errors: &[(usize, ParseError)]) -> String {
+    let described: Vec<String> = errors
+        .iter()
+        .map(|(line, e)| format!("{source}:{line}: {e}"))
+        .collect();
 
-    poly
+    described.join("\n")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::grapher::equation::{Calculate, Term};
 
     #[test]
     fn normal_polynomial() {
@@ -173,7 +181,7 @@ mod tests {
             Term::new(-1., 0),
         ]);
 
-        let result = parse_equation(input);
+        let result = parse_equation(&input).unwrap();
 
         assert_eq!(result, expected);
     }
@@ -183,7 +191,7 @@ mod tests {
         let neg = String::from("-2.1x^3 - 1");
         let expected = Polynomial::new(vec![Term::new(-2.1, 3), Term::new(-1., 0)]);
 
-        let result = parse_equation(neg);
+        let result = parse_equation(&neg).unwrap();
 
         assert_eq!(result, expected);
     }
@@ -193,7 +201,7 @@ mod tests {
         let pos = String::from("+1.3x^2 + 2");
         let expected = Polynomial::new(vec![Term::new(1.3, 2), Term::new(2., 0)]);
 
-        let result = parse_equation(pos);
+        let result = parse_equation(&pos).unwrap();
 
         assert_eq!(result, expected);
     }
@@ -207,7 +215,7 @@ mod tests {
             Term::new(-3.7, 0),
         ]);
 
-        let result = parse_equation(input);
+        let result = parse_equation(&input).unwrap();
 
         assert_eq!(result, expected);
     }
@@ -222,8 +230,192 @@ mod tests {
             Term::new(-3., 0),
         ]);
 
-        let result = parse_equation(input);
+        let result = parse_equation(&input).unwrap();
 
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn error_points_at_bad_character() {
+        let err = parse_equation("2x^2 + &").unwrap_err();
+
+        assert_eq!(err.span, 7..8);
+        assert_eq!(
+            err.to_string(),
+            "unexpected character '&'\n  2x^2 + &\n         ^"
+        );
+    }
+
+    #[test]
+    fn error_on_non_integer_exponent() {
+        let err = parse_equation("4.2x^2.5 - 1").unwrap_err();
+
+        assert_eq!(err.span, 5..8);
+        assert!(err.to_string().ends_with("\n       ^^^"));
+    }
+
+    #[test]
+    fn error_on_missing_term() {
+        let err = parse_equation("3x^2 -\n").unwrap_err();
+
+        assert_eq!(err.span, 6..6);
+        assert_eq!(err.message, "expected a term at the end of the equation");
+    }
+
+    #[test]
+    fn error_on_missing_operator() {
+        let err = parse_equation("2x 3").unwrap_err();
+
+        assert_eq!(err.span, 3..4);
+    }
+
+    #[test]
+    fn expands_grouping_and_implicit_multiplication() {
+        let input = "2(x+1)^3 - x(x-4)";
+        let expected = Polynomial::new(vec![
+            Term::new(2., 3),
+            Term::new(5., 2),
+            Term::new(10., 1),
+            Term::new(2., 0),
+        ]);
+
+        let result = parse_equation(input).unwrap();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn expands_product_of_factors() {
+        let input = "(x-1)(x+2)(x-3)";
+        let expected = Polynomial::new(vec![
+            Term::new(1., 3),
+            Term::new(-2., 2),
+            Term::new(-5., 1),
+            Term::new(6., 0),
+        ]);
+
+        let result = parse_equation(input).unwrap();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn non_polynomials_parse_as_expressions() {
+        let result = parse_expression("sin(x)/x").unwrap();
+
+        assert!(matches!(result, Equation::Expression(_)));
+        assert_eq!(result.to_string(), "y = sin(x) / x");
+
+        let err = parse_equation("sin(x)/x").unwrap_err();
+        assert_eq!(err.span, 0..6);
+    }
+
+    #[test]
+    fn error_on_unclosed_paren() {
+        let err = parse_expression("2(x+1").unwrap_err();
+
+        assert_eq!(err.span, 1..2);
+        assert_eq!(err.message, "unclosed '('");
+    }
+
+    #[test]
+    fn divides_polynomials_exactly() {
+        let result = parse_equation("(x^2 - 1)/(x - 1)").unwrap();
+        assert_eq!(
+            result,
+            Polynomial::new(vec![Term::new(1., 1), Term::new(1., 0)])
+        );
+
+        let err = parse_equation("x/(x - 1)").unwrap_err();
+        assert_eq!(err.span, 2..9);
+        assert!(matches!(
+            parse_expression("x/(x - 1)").unwrap(),
+            Equation::Expression(_)
+        ));
+    }
+
+    #[test]
+    fn polynomial_operations() {
+        let gcd = parse_equation("gcd(x^2 - 1, x^2 + 2x + 1)").unwrap();
+        let quotient = parse_equation("quot(x^3 - 1, x - 1)").unwrap();
+        let remainder = parse_equation("rem(x^3, x - 2)").unwrap();
+        let composed = parse_equation("compose(x^2, x + 1)").unwrap();
+
+        assert_eq!(
+            gcd,
+            Polynomial::new(vec![Term::new(1., 1), Term::new(1., 0)])
+        );
+        assert_eq!(
+            quotient,
+            Polynomial::new(vec![Term::new(1., 2), Term::new(1., 1), Term::new(1., 0)])
+        );
+        assert_eq!(remainder, Polynomial::new(vec![Term::new(8., 0)]));
+        assert_eq!(composed.calc(2.), 9.);
+
+        // mixed with functions they're expanded first
+        let mixed = parse_expression("sin(x) + quot(x^2, x)").unwrap();
+        assert_eq!(mixed.to_string(), "y = sin(x) + x");
+
+        let err = parse_equation("gcd(x^2 x)").unwrap_err();
+        assert_eq!(err.message, "expected ',' between the two arguments of gcd");
+    }
+
+    #[test]
+    fn lines_skip_comments_and_blanks() {
+        let text = "# roots at -1 and 1\nx^2 - 1\n\n  sin(x) # a wave\n";
+        let equations = parse_lines(text).unwrap();
+        assert_eq!(equations.len(), 2);
+        assert_eq!(equations[1].calc(0.), 0.);
+    }
+
+    #[test]
+    fn every_bad_line_is_reported() {
+        let errors = parse_lines("x\n2x + q\n\n(x - 1\n").unwrap_err();
+        let lines: Vec<usize> = errors.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![2, 4]);
+
+        let described = describe_line_errors("eqs.txt", &errors);
+        assert!(described.starts_with("eqs.txt:2: unknown name 'q'\n  2x + q\n       ^\n"));
+        assert!(described.contains("\neqs.txt:4: "));
+    }
+
+    #[test]
+    fn error_on_degree_too_high() {
+        for input in ["x^2147483648", "x^2147483647*x", "(x+1)^3000"] {
+            let err = parse_expression(input).unwrap_err();
+            assert_eq!(err.message, "polynomials can't go above x^1000", "{input}");
+        }
+        assert_eq!(parse_expression("x^2147483648").unwrap_err().span, 2..12);
+
+        // too high a product is left to be calculated as an expression
+        let err = parse_equation("x^600 * x^600").unwrap_err();
+        assert_eq!(err.message, "polynomials can't go above x^1000");
+
+        // constants aren't capped
+        assert_eq!(parse_equation("1^5000").unwrap().calc(0.), 1.);
+        // coefficients that overflow aren't multiplied out, it's calculated as an expression instead
+        let err = parse_equation("(x+1)^1000").unwrap_err();
+        assert_eq!(err.message, "the coefficients are too big to multiply out");
+        assert_eq!(parse_expression("(x+1)^1000").unwrap().calc(0.), 1.);
+        // only polynomials are multiplied out
+        assert!(parse_expression("sin(x)^2000").is_ok());
+    }
+
+    #[test]
+    fn small_coefficients_are_kept() {
+        let poly = parse_equation("x^2 - 0.00001").unwrap();
+        assert_eq!(poly.calc(0.), -0.00001);
+
+        let poly = parse_equation("0.001x^2 - 0.000001").unwrap();
+        let roots: Vec<f32> = poly.real_roots().iter().map(|r| r.x).collect();
+        assert_eq!(roots.len(), 2);
+        assert!((roots[1] - 0.0316).abs() < 1e-4);
+
+        let root = parse_equation("(x - 0.1)^5").unwrap().real_roots();
+        assert_eq!(root.len(), 1);
+        assert!((root[0].x - 0.1).abs() < 1e-4 && root[0].multiplicity == 5);
+
+        // big coefficients don't overflow either
+        assert_eq!(parse_equation("(x + 1)^127").unwrap().calc(0.), 1.);
+    }
 }
@@ -0,0 +1,45 @@
+use std::fmt::Display;
+use std::ops::Range;
+
+// error for input that can't be parsed into an equation
+// span is the byte range of the input that caused the problem
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Range<usize>,
+    input: String,
+}
+
+impl ParseError {
+    pub fn new(message: impl Into<String>, span: Range<usize>, input: &str) -> Self {
+        ParseError {
+            message: message.into(),
+            span,
+            input: input.to_string(),
+        }
+    }
+}
+
+// prints the message, then the input with the span underlined, eg:
+// unexpected character 'q'
+//   2x^2 + q
+//          ^
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let line = self.input.trim_end_matches(['\n', '\r']);
+
+        // count chars rather than bytes so the caret lines up with multi-byte characters
+        let start = line.get(..self.span.start).unwrap_or(line).chars().count();
+        let width = line
+            .get(self.span.clone())
+            .map(|s| s.chars().count())
+            .unwrap_or(0)
+            .max(1);
+
+        writeln!(f, "{}", self.message)?;
+        writeln!(f, "  {}", line)?;
+        write!(f, "  {}{}", " ".repeat(start), "^".repeat(width))
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -0,0 +1,389 @@
+use std::ops::Range;
+
+use super::error::ParseError;
+use super::tokenizer::{tokenize, Operation, Token, TokenKind};
+use crate::grapher::equation::Polynomial;
+use crate::grapher::expression::{Expression, Function};
+
+// recursive descent over the tokens:
+// sum     := product (('+' | '-') product)*
+// product := unary (('*' | '/') unary | power)*     a power right after a factor is implicit multiplication
+// unary   := ('-' | '+') unary | power
+// power   := atom ('^' unary)?
+// atom    := number | x | constant | function '(' sum ')' | operation '(' sum ',' sum ')' | '(' sum ')'
+struct Grammar<'a> {
+    input: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+#[derive(Debug)]
+enum Node {
+    Number(f32),
+    X,
+    Neg(Box<SpannedNode>),
+    Binary(BinaryOp, Box<SpannedNode>, Box<SpannedNode>),
+    Call(Function, Box<SpannedNode>),
+    Operation(Operation, Box<SpannedNode>, Box<SpannedNode>),
+}
+
+// syntax tree that remembers where each node came from, for error messages
+#[derive(Debug)]
+struct SpannedNode {
+    node: Node,
+    span: Range<usize>,
+}
+
+// parses anything that expands to a polynomial, eg: 2(x+1)^3 - x(x-4)
+pub fn parse_polynomial(input: &str) -> Result<Polynomial, ParseError> {
+    let tree = parse_tree(input)?;
+
+    Ok(expand(&tree, input)?.collect_like_terms())
+}
+
+// parses any equation in x as a general expression, eg: sin(x)/x
+pub fn parse_expression(input: &str) -> Result<Expression, ParseError> {
+    let tree = parse_tree(input)?;
+
+    to_expression(&tree, input)
+}
+
+fn parse_tree(input: &str) -> Result<SpannedNode, ParseError> {
+    let tokens = tokenize(input)?;
+    let mut grammar = Grammar {
+        input,
+        tokens,
+        position: 0,
+    };
+
+    let tree = grammar.sum()?;
+
+    let token = grammar.peek();
+    match token.kind {
+        TokenKind::End => Ok(tree),
+        TokenKind::RParen => Err(grammar.error("unmatched ')'", token.span.clone())),
+        _ => Err(grammar.error(
+            "expected an operator such as '+' or '*' before this",
+            token.span.clone(),
+        )),
+    }
+}
+
+impl Grammar<'_> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position]
+    }
+
+    // the End token is never consumed, so peek always has something to look at
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.position].clone();
+        if token.kind != TokenKind::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn error(&self, message: impl Into<String>, span: Range<usize>) -> ParseError {
+        ParseError::new(message, span, self.input)
+    }
+
+    fn binary(op: BinaryOp, left: SpannedNode, right: SpannedNode) -> SpannedNode {
+        let span = left.span.start..right.span.end;
+        SpannedNode {
+            node: Node::Binary(op, Box::new(left), Box::new(right)),
+            span,
+        }
+    }
+
+    fn sum(&mut self) -> Result<SpannedNode, ParseError> {
+        let mut left = self.product()?;
+
+        loop {
+            let op = match self.peek().kind {
+                TokenKind::Plus => BinaryOp::Add,
+                TokenKind::Minus => BinaryOp::Sub,
+                _ => return Ok(left),
+            };
+            self.advance();
+            let right = self.product()?;
+            left = Self::binary(op, left, right);
+        }
+    }
+
+    fn product(&mut self) -> Result<SpannedNode, ParseError> {
+        let mut left = self.unary()?;
+
+        loop {
+            let (op, right) = match self.peek().kind {
+                TokenKind::Star => {
+                    self.advance();
+                    (BinaryOp::Mul, self.unary()?)
+                }
+                TokenKind::Slash => {
+                    self.advance();
+                    (BinaryOp::Div, self.unary()?)
+                }
+                // a number can't be implicitly multiplied, "2x 3" is more likely a typo than 6x
+                TokenKind::X
+                | TokenKind::Constant(_)
+                | TokenKind::Function(_)
+                | TokenKind::LParen => (BinaryOp::Mul, self.power()?),
+                _ => return Ok(left),
+            };
+            left = Self::binary(op, left, right);
+        }
+    }
+
+    fn unary(&mut self) -> Result<SpannedNode, ParseError> {
+        match self.peek().kind {
+            TokenKind::Minus => {
+                let start = self.advance().span.start;
+                let operand = self.unary()?;
+                let span = start..operand.span.end;
+                Ok(SpannedNode {
+                    node: Node::Neg(Box::new(operand)),
+                    span,
+                })
+            }
+            TokenKind::Plus => {
+                self.advance();
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<SpannedNode, ParseError> {
+        let base = self.atom()?;
+
+        if self.peek().kind != TokenKind::Caret {
+            return Ok(base);
+        }
+        self.advance();
+
+        let exponent = match self.peek().kind {
+            TokenKind::End => {
+                let span = self.peek().span.clone();
+                return Err(self.error("expected an exponent after '^'", span));
+            }
+            _ => self.unary()?,
+        };
+
+        Ok(Self::binary(BinaryOp::Pow, base, exponent))
+    }
+
+    fn atom(&mut self) -> Result<SpannedNode, ParseError> {
+        let token = self.advance();
+
+        let node = match token.kind {
+            TokenKind::Number(n) | TokenKind::Constant(n) => Node::Number(n),
+            TokenKind::X => Node::X,
+            TokenKind::LParen => {
+                let inner = self.sum()?;
+                let close = self.closing_paren(&token)?;
+                return Ok(SpannedNode {
+                    node: inner.node,
+                    span: token.span.start..close.end,
+                });
+            }
+            TokenKind::Function(function) => {
+                let open = self.advance();
+                if open.kind != TokenKind::LParen {
+                    return Err(
+                        self.error(format!("expected '(' after {}", function.name()), open.span)
+                    );
+                }
+                let arg = self.sum()?;
+                let close = self.closing_paren(&open)?;
+                return Ok(SpannedNode {
+                    node: Node::Call(function, Box::new(arg)),
+                    span: token.span.start..close.end,
+                });
+            }
+            TokenKind::Operation(op) => {
+                let open = self.advance();
+                if open.kind != TokenKind::LParen {
+                    return Err(self.error(format!("expected '(' after {}", op.name()), open.span));
+                }
+                let left = self.sum()?;
+                let comma = self.advance();
+                if comma.kind != TokenKind::Comma {
+                    return Err(self.error(
+                        format!("expected ',' between the two arguments of {}", op.name()),
+                        comma.span,
+                    ));
+                }
+                let right = self.sum()?;
+                let close = self.closing_paren(&open)?;
+                return Ok(SpannedNode {
+                    node: Node::Operation(op, Box::new(left), Box::new(right)),
+                    span: token.span.start..close.end,
+                });
+            }
+            TokenKind::End => {
+                return Err(self.error("expected a term at the end of the equation", token.span));
+            }
+            TokenKind::RParen => return Err(self.error("unmatched ')'", token.span)),
+            _ => {
+                return Err(self.error("expected a number, x, a function or '('", token.span));
+            }
+        };
+
+        Ok(SpannedNode {
+            node,
+            span: token.span,
+        })
+    }
+
+    fn closing_paren(&mut self, open: &Token) -> Result<Range<usize>, ParseError> {
+        if self.peek().kind == TokenKind::RParen {
+            Ok(self.advance().span)
+        } else {
+            Err(self.error("unclosed '('", open.span.clone()))
+        }
+    }
+}
+
+// multiplying out anything higher takes too long to be worth waiting for
+const MAX_DEGREE: u32 = 1000;
+
+fn too_high() -> String {
+    format!("polynomials can't go above x^{MAX_DEGREE}")
+}
+
+// multiplies out the tree into a polynomial, failing on anything that isn't one
+fn expand(tree: &SpannedNode, input: &str) -> Result<Polynomial, ParseError> {
+    let error =
+        |message: String, span: &Range<usize>| ParseError::new(message, span.clone(), input);
+
+    let polynomial = match &tree.node {
+        Node::Number(n) => Polynomial::constant(*n),
+        Node::X => Polynomial::x(),
+        Node::Neg(operand) => -expand(operand, input)?,
+        Node::Call(function, arg) => match expand(arg, input)?.as_constant() {
+            Some(value) => Polynomial::constant(function.apply(value)),
+            None => {
+                let message = format!("{}(x) is not a polynomial", function.name());
+                return Err(error(message, &tree.span));
+            }
+        },
+        Node::Operation(op, left, right) => {
+            let left_poly = expand(left, input)?;
+            let right_poly = expand(right, input)?;
+
+            match op {
+                Operation::Gcd => left_poly.gcd(&right_poly),
+                Operation::Compose => {
+                    if left_poly.degree() as u64 * right_poly.degree() as u64 > MAX_DEGREE as u64 {
+                        return Err(error(too_high(), &tree.span));
+                    }
+                    left_poly.compose(&right_poly)
+                }
+                Operation::Quotient | Operation::Remainder => {
+                    if right_poly.is_zero() {
+                        return Err(error("division by zero".into(), &right.span));
+                    }
+                    let (quotient, remainder) = left_poly.div_rem(&right_poly);
+                    match op {
+                        Operation::Quotient => quotient,
+                        _ => remainder,
+                    }
+                }
+            }
+        }
+        Node::Binary(op, left, right) => {
+            let left_poly = expand(left, input)?;
+            let right_poly = expand(right, input)?;
+
+            match op {
+                BinaryOp::Add => left_poly + right_poly,
+                BinaryOp::Sub => left_poly - right_poly,
+                BinaryOp::Mul => {
+                    if left_poly.degree() + right_poly.degree() > MAX_DEGREE {
+                        return Err(error(too_high(), &tree.span));
+                    }
+                    left_poly * right_poly
+                }
+                BinaryOp::Div => {
+                    if right_poly.is_zero() {
+                        return Err(error("division by zero".into(), &right.span));
+                    }
+                    // anything that doesn't divide exactly is a rational function, not a polynomial
+                    let (quotient, remainder) = left_poly.div_rem(&right_poly);
+                    if !remainder.simplify().is_zero() {
+                        let message = "the division leaves a remainder, so this isn't a polynomial";
+                        return Err(error(message.into(), &right.span));
+                    }
+                    quotient
+                }
+                BinaryOp::Pow => {
+                    let n = match right_poly.as_constant() {
+                        Some(n) if n >= 0. && n.fract() == 0. && n <= i32::MAX as f32 => n as u32,
+                        Some(n) => {
+                            let message =
+                                format!("exponents must be non-negative integers, found {}", n);
+                            return Err(error(message, &right.span));
+                        }
+                        None => {
+                            return Err(error("exponents can't contain x".into(), &right.span));
+                        }
+                    };
+                    // constants can be raised to anything, it's only a few multiplications
+                    let degree = left_poly.degree() as u64 * n as u64;
+                    if degree > MAX_DEGREE as u64 {
+                        return Err(error(too_high(), &right.span));
+                    }
+                    left_poly.pow(n)
+                }
+            }
+        }
+    };
+
+    // f32 coefficients overflow long before the degree cap, eg: (x+1)^1000
+    if !polynomial.is_finite() {
+        let message = "the coefficients are too big to multiply out";
+        return Err(error(message.into(), &tree.span));
+    }
+
+    Ok(polynomial)
+}
+
+// polynomial operations have no expression form, so they're expanded and converted
+fn to_expression(tree: &SpannedNode, input: &str) -> Result<Expression, ParseError> {
+    let expression = match &tree.node {
+        Node::Number(n) => Expression::constant(*n),
+        Node::X => Expression::x(),
+        Node::Neg(operand) => -to_expression(operand, input)?,
+        Node::Call(function, arg) => Expression::call(*function, to_expression(arg, input)?),
+        Node::Operation(..) => expand(tree, input)?.collect_like_terms().into(),
+        Node::Binary(op, left, right) => {
+            // a polynomial raised too high would only go to 0 or inf, so it's an error either way
+            if let (BinaryOp::Pow, Ok(base)) = (op, expand(left, input)) {
+                let exponent = expand(right, input).ok().and_then(|p| p.as_constant());
+                if base.degree() > 0 && exponent.is_some_and(|n| n > MAX_DEGREE as f32) {
+                    return Err(ParseError::new(too_high(), right.span.clone(), input));
+                }
+            }
+            let left = to_expression(left, input)?;
+            let right = to_expression(right, input)?;
+            match op {
+                BinaryOp::Add => left + right,
+                BinaryOp::Sub => left - right,
+                BinaryOp::Mul => left * right,
+                BinaryOp::Div => left / right,
+                BinaryOp::Pow => left.pow(right),
+            }
+        }
+    };
+
+    Ok(expression)
+}
@@ -0,0 +1,185 @@
+use std::ops::Range;
+
+use super::error::ParseError;
+use crate::grapher::expression::Function;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Number(f32),
+    X,
+    Constant(f32),
+    Function(Function),
+    Operation(Operation),
+    Caret,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    LParen,
+    RParen,
+    Comma,
+    End,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Range<usize>,
+}
+
+// polynomial algebra that takes two polynomials, eg: gcd(x^2 - 1, x + 1)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operation {
+    Gcd,
+    Quotient,
+    Remainder,
+    Compose,
+}
+
+impl Operation {
+    const ALL: [Operation; 4] = [
+        Operation::Gcd,
+        Operation::Quotient,
+        Operation::Remainder,
+        Operation::Compose,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::Gcd => "gcd",
+            Operation::Quotient => "quot",
+            Operation::Remainder => "rem",
+            Operation::Compose => "compose",
+        }
+    }
+}
+
+// named constants that can be used in place of a number
+const CONSTANTS: [(&str, f32); 2] = [("pi", std::f32::consts::PI), ("e", std::f32::consts::E)];
+
+// splits the input into tokens, skipping whitespace
+// always ends with an End token spanning the end of the input
+pub fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let kind = match c {
+            c if c.is_whitespace() => continue,
+            '^' => TokenKind::Caret,
+            '+' => TokenKind::Plus,
+            '-' => TokenKind::Minus,
+            '*' => TokenKind::Star,
+            '/' => TokenKind::Slash,
+            '(' => TokenKind::LParen,
+            ')' => TokenKind::RParen,
+            ',' => TokenKind::Comma,
+            c if c.is_ascii_digit() || c == '.' => {
+                let end = take_while(&mut chars, start, c, |c| c.is_ascii_digit() || c == '.');
+
+                let text = &input[start..end];
+                let value = text.parse::<f32>().map_err(|_| {
+                    ParseError::new(format!("invalid number '{}'", text), start..end, input)
+                })?;
+
+                tokens.push(Token {
+                    kind: TokenKind::Number(value),
+                    span: start..end,
+                });
+                continue;
+            }
+            c if c.is_ascii_alphabetic() => {
+                let end = take_while(&mut chars, start, c, |c| c.is_ascii_alphabetic());
+                split_names(input, start..end, &mut tokens)?;
+                continue;
+            }
+            other => {
+                let span = start..start + other.len_utf8();
+                return Err(ParseError::new(
+                    format!("unexpected character '{}'", other),
+                    span,
+                    input,
+                ));
+            }
+        };
+
+        tokens.push(Token {
+            kind,
+            span: start..start + c.len_utf8(),
+        });
+    }
+
+    let end = input.trim_end().len();
+    tokens.push(Token {
+        kind: TokenKind::End,
+        span: end..end,
+    });
+
+    Ok(tokens)
+}
+
+// consumes chars while they match, returning the end of the run started by first
+fn take_while(
+    chars: &mut std::iter::Peekable<std::str::CharIndices>,
+    start: usize,
+    first: char,
+    matches: impl Fn(char) -> bool,
+) -> usize {
+    let mut end = start + first.len_utf8();
+    while let Some(&(i, next)) = chars.peek() {
+        if !matches(next) {
+            break;
+        }
+        end = i + next.len_utf8();
+        chars.next();
+    }
+    end
+}
+
+// a run of letters can hold several names with implicit multiplication, eg: "xsin" or "pix"
+// so take the longest known name from the front each time
+fn split_names(input: &str, span: Range<usize>, tokens: &mut Vec<Token>) -> Result<(), ParseError> {
+    let mut start = span.start;
+
+    while start < span.end {
+        let rest = input[start..span.end].to_ascii_lowercase();
+
+        let functions = Function::ALL
+            .into_iter()
+            .map(|f| (f.name(), TokenKind::Function(f)));
+        let operations = Operation::ALL
+            .into_iter()
+            .map(|op| (op.name(), TokenKind::Operation(op)));
+        let constants = CONSTANTS
+            .into_iter()
+            .map(|(name, value)| (name, TokenKind::Constant(value)));
+        let x = std::iter::once(("x", TokenKind::X));
+
+        let longest = functions
+            .chain(operations)
+            .chain(constants)
+            .chain(x)
+            .filter(|(name, _)| rest.starts_with(name))
+            .max_by_key(|(name, _)| name.len());
+
+        match longest {
+            Some((name, kind)) => {
+                let end = start + name.len();
+                tokens.push(Token {
+                    kind,
+                    span: start..end,
+                });
+                start = end;
+            }
+            None => {
+                return Err(ParseError::new(
+                    format!("unknown name '{}'", &input[start..span.end]),
+                    start..span.end,
+                    input,
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,235 @@
+use winit::dpi::{LogicalPosition, LogicalSize};
+
+use crate::grapher::drawing::{svg::Svg, Color, Paint, Surface};
+use crate::grapher::equation::Equation;
+use crate::grapher::graph::{AxisModes, Graph, Scale, DEFAULT_SCALE};
+use crate::grapher::style::Style;
+
+// the cursor snaps to a curve this close to it
+const SNAP_DISTANCE_PX: f32 = 8.;
+
+// the window starts this big, and exports are this big without one
+pub const DEFAULT_SIZE: LogicalSize<f32> = LogicalSize {
+    width: 1000.,
+    height: 600.,
+};
+
+// the part of the graph that's shown, in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct View {
+    pub size: LogicalSize<f32>,
+    pub scale: Scale,
+    pub offset: LogicalPosition<f32>,
+    pub axes: AxisModes,
+}
+
+impl View {
+    // the default scale with the origin in the middle
+    pub fn new(size: LogicalSize<f32>) -> Self {
+        View {
+            size,
+            scale: Scale::uniform(DEFAULT_SCALE),
+            offset: LogicalPosition::default(),
+            axes: AxisModes::default(),
+        }
+    }
+
+    pub fn graph<'a, S: Surface>(&self, surface: &'a mut S) -> Graph<'a, S> {
+        Graph::new(self.size, self.scale, self.offset, surface).with_axes(self.axes)
+    }
+}
+
+// extra curves drawn along with the equations, toggled from the keyboard and mouse
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Overlays {
+    // where the cursor is, to draw the crosshair at
+    pub crosshair: Option<LogicalPosition<f32>>,
+    pub derivative_order: u32,
+    pub roots: bool,
+    pub critical_points: bool,
+    pub intersections: bool,
+    // x bounds of the shaded area under the curves, selected with shift + drag
+    pub area: Option<(f32, f32)>,
+}
+
+// an equation along with how its curve is drawn
+pub struct Plot {
+    pub equation: Equation,
+    pub style: Style,
+}
+
+// each equation gets the next style from the palette
+pub fn plots(equations: Vec<Equation>) -> Vec<Plot> {
+    equations
+        .into_iter()
+        .enumerate()
+        .map(|(i, equation)| Plot {
+            equation,
+            style: Style::from_palette(i),
+        })
+        .collect()
+}
+
+// everything on screen: the grid, curves, overlays and legend
+pub fn draw_scene<S: Surface>(surface: &mut S, view: View, plots: &[Plot], overlays: Overlays) {
+    let mut graph1 = view.graph(surface);
+    graph1.init_graph();
+
+    // shade first so the curves are drawn over it
+    if let Some((a, b)) = overlays.area {
+        for plot in plots {
+            graph1.shade_area(&plot.equation, a, b);
+        }
+    }
+
+    let equations: Vec<&Equation> = plots.iter().map(|plot| &plot.equation).collect();
+    // the curve near the cursor, if any, is highlighted and the crosshair snaps to it
+    let cursor = overlays.crosshair.map(|cursor| (cursor.x, cursor.y));
+    let hovered =
+        cursor.and_then(|cursor| graph1.nearest_curve(&equations, cursor, SNAP_DISTANCE_PX));
+
+    for (i, plot) in plots.iter().enumerate() {
+        let style = match hovered {
+            Some((hovered, _)) if hovered == i => plot.style.highlighted(),
+            _ => plot.style,
+        };
+        graph1.graph_equation(&plot.equation, &style);
+
+        // derivatives are only known for polynomials
+        if let Equation::Polynomial(poly) = &plot.equation {
+            for order in 1..=overlays.derivative_order {
+                let derivative = poly.nth_derivative(order);
+                graph1.graph_equation_with_paint(&derivative, &derivative_paint(order));
+            }
+        }
+    }
+
+    // markers go on top of every curve
+    if overlays.roots {
+        for plot in plots {
+            graph1.mark_roots(&plot.equation);
+        }
+    }
+    if overlays.critical_points {
+        for plot in plots {
+            // extrema and inflection points are only found for polynomials
+            if let Equation::Polynomial(poly) = &plot.equation {
+                graph1.mark_critical_points(&poly.critical_points());
+            }
+        }
+    }
+    if overlays.intersections {
+        graph1.mark_intersections(&equations);
+    }
+
+    let legend: Vec<(String, Style)> = plots
+        .iter()
+        .map(|plot| (plot.equation.to_string(), plot.style))
+        .collect();
+    graph1.draw_legend(&legend);
+
+    if let Some(cursor) = cursor {
+        let snapped = hovered.map(|(i, point)| (point, plots[i].style.color));
+        graph1.draw_crosshair(cursor, snapped);
+    }
+}
+
+// the scene as an svg document, without the crosshair since there's no cursor on paper
+pub fn to_svg(view: View, plots: &[Plot], overlays: Overlays) -> String {
+    let mut svg = Svg::new(view.size.width, view.size.height, Color::black());
+    let overlays = Overlays {
+        crosshair: None,
+        ..overlays
+    };
+    draw_scene(&mut svg, view, plots, overlays);
+    svg.finish()
+}
+
+// the scene as a png image, drawn in software so it works without a gpu or display
+#[cfg(not(target_arch = "wasm32"))]
+pub fn to_png(view: View, plots: &[Plot], overlays: Overlays) -> Result<Vec<u8>, String> {
+    use crate::grapher::drawing::raster::Raster;
+
+    let mut raster = Raster::new(view.size.width, view.size.height, 1., Color::black())
+        .ok_or_else(|| String::from("the image needs to be at least a pixel wide and high"))?;
+    let overlays = Overlays {
+        crosshair: None,
+        ..overlays
+    };
+    draw_scene(&mut raster, view, plots, overlays);
+    Ok(raster.encode_png())
+}
+
+// eg: graph.svg, or graph-2.svg if that's taken, so exports don't overwrite each other
+// only the window exports to a made up name, and there are no files in the browser
+#[cfg(not(target_arch = "wasm32"))]
+pub fn unused_path(stem: &str, extension: &str) -> std::path::PathBuf {
+    use std::path::PathBuf;
+
+    let mut path = PathBuf::from(format!("{stem}.{extension}"));
+    let mut n = 2;
+    while path.exists() {
+        path = PathBuf::from(format!("{stem}-{n}.{extension}"));
+        n += 1;
+    }
+    path
+}
+
+// f' in orange, f'' in purple, thinner than the equations themselves
+fn derivative_paint(order: u32) -> Paint {
+    let color = match order {
+        1 => Color::rgb(255, 165, 0),
+        _ => Color::rgb(190, 80, 255),
+    };
+    Paint::color(color).with_line_width(0.75)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grapher::equation::{Polynomial, PolynomialBuilder};
+
+    #[test]
+    fn svg_keeps_curves_as_beziers() {
+        let quad: Polynomial = PolynomialBuilder::new().plus_x_squared_times(0.5).build();
+        let cubic = Polynomial::x().pow(3) - Polynomial::x();
+        let plots = plots(vec![quad.into(), cubic.into()]);
+
+        let view = View::new(LogicalSize::new(800., 400.));
+        let svg = to_svg(view, &plots, Overlays::default());
+
+        assert!(svg.contains("width=\"800\" height=\"400\""));
+        // the parabola is a single quadratic bezier and the cubic a cubic one
+        assert!(svg.contains("Q"));
+        assert!(svg.contains("C"));
+        // the legend
+        assert!(svg.contains(&format!(">{}</text>", plots[0].equation)));
+    }
+
+    #[test]
+    fn png_is_drawn_headless() {
+        let line: Polynomial = PolynomialBuilder::new().plus_const(1.).build();
+        let plots = plots(vec![line.into()]);
+        let view = View::new(LogicalSize::new(400., 200.));
+
+        let png = to_png(view, &plots, Overlays::default()).unwrap();
+        let image = tiny_skia::Pixmap::decode_png(&png).unwrap();
+        assert_eq!((image.width(), image.height()), (400, 200));
+
+        let rgb = |x, y| {
+            let pixel = image.pixel(x, y).unwrap();
+            Color::rgb(pixel.red(), pixel.green(), pixel.blue())
+        };
+        // y = 1 is 50 pixels above the x axis, away from the gridlines
+        assert_eq!(rgb(310, 49), plots[0].style.color);
+        assert_eq!(rgb(310, 100), Color::rgb(0, 255, 0));
+        assert_eq!(rgb(310, 140), Color::black());
+
+        assert!(to_png(
+            View::new(LogicalSize::new(0., 200.)),
+            &plots,
+            Overlays::default()
+        )
+        .is_err());
+    }
+}
@@ -1,2 +1,7 @@
+pub mod analysis;
+pub mod drawing;
 pub mod equation;
+pub mod expression;
 pub mod graph;
+pub mod sampling;
+pub mod style;
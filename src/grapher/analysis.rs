@@ -0,0 +1,357 @@
+// numeric helpers for analysing polynomials
+// polynomials here are dense f64 coefficients where the index is the power, eg: [1, 0, 2] = 2x^2 + 1
+// f64 so that the long divisions in gcd and sturm sequences don't lose the f32 input precision
+
+// remainders smaller than this (relative to the dividend) are treated as zero,
+// a bit above f32 precision since the coefficients come from f32
+const ZERO_TOLERANCE: f64 = 1e-6;
+
+fn max_abs(p: &[f64]) -> f64 {
+    p.iter().fold(0., |max, c| max.max(c.abs()))
+}
+
+// drops leading zero coefficients so the last one is the highest power
+fn trim(mut p: Vec<f64>) -> Vec<f64> {
+    while p.last() == Some(&0.) {
+        p.pop();
+    }
+    p
+}
+
+fn eval(p: &[f64], x: f64) -> f64 {
+    p.iter().rev().fold(0., |acc, c| acc * x + c)
+}
+
+fn derivative(p: &[f64]) -> Vec<f64> {
+    p.iter()
+        .enumerate()
+        .skip(1)
+        .map(|(power, c)| c * power as f64)
+        .collect()
+}
+
+// polynomial long division, returns (quotient, remainder)
+pub fn div_rem(a: &[f64], b: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    let b = trim(b.to_vec());
+    let lead = *b.last().expect("division by the zero polynomial");
+
+    let mut remainder = trim(a.to_vec());
+    if remainder.len() < b.len() {
+        return (vec![], remainder);
+    }
+
+    let mut quotient = vec![0.; remainder.len() - b.len() + 1];
+    for shift in (0..quotient.len()).rev() {
+        let c = remainder[shift + b.len() - 1] / lead;
+        quotient[shift] = c;
+        for (i, bc) in b.iter().enumerate() {
+            remainder[shift + i] -= c * bc;
+        }
+        // the leading term is cancelled exactly, even if rounding says otherwise
+        remainder[shift + b.len() - 1] = 0.;
+    }
+
+    (trim(quotient), trim(remainder))
+}
+
+fn normalize(p: &[f64]) -> Vec<f64> {
+    let max = max_abs(p);
+    if max == 0. {
+        return p.to_vec();
+    }
+    p.iter().map(|c| c / max).collect()
+}
+
+fn monic(p: &[f64]) -> Vec<f64> {
+    match p.last() {
+        Some(&lead) if lead != 0. => p.iter().map(|c| c / lead).collect(),
+        _ => p.to_vec(),
+    }
+}
+
+// remainder of a / b with both scaled to unit size, so the zero tolerance means the same at every step
+fn relative_rem(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let (_, remainder) = div_rem(&normalize(a), &normalize(b));
+    if max_abs(&remainder) <= ZERO_TOLERANCE {
+        vec![]
+    } else {
+        remainder
+    }
+}
+
+// monic greatest common divisor using the euclidean algorithm
+pub fn gcd(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut a = trim(a.to_vec());
+    let mut b = trim(b.to_vec());
+    if a.len() < b.len() {
+        std::mem::swap(&mut a, &mut b);
+    }
+
+    while !b.is_empty() {
+        let remainder = relative_rem(&a, &b);
+        a = b;
+        b = remainder;
+    }
+
+    monic(&a)
+}
+
+// p, p', then the negated remainders until they run out
+fn sturm_sequence(p: &[f64]) -> Vec<Vec<f64>> {
+    let mut sequence = vec![p.to_vec(), derivative(p)];
+
+    loop {
+        let n = sequence.len();
+        if sequence[n - 1].len() <= 1 {
+            break;
+        }
+        let remainder = relative_rem(&sequence[n - 2], &sequence[n - 1]);
+        if remainder.is_empty() {
+            break;
+        }
+        sequence.push(remainder.iter().map(|c| -c).collect());
+    }
+
+    sequence
+}
+
+fn sign_changes(sequence: &[Vec<f64>], x: f64) -> usize {
+    let signs = sequence
+        .iter()
+        .map(|p| eval(p, x))
+        .filter(|v| *v != 0.)
+        .map(|v| v > 0.);
+
+    let mut changes = 0;
+    let mut previous = None;
+    for sign in signs {
+        if previous.is_some_and(|p| p != sign) {
+            changes += 1;
+        }
+        previous = Some(sign);
+    }
+    changes
+}
+
+// every real root lies within this distance of 0 (Cauchy's bound)
+fn root_bound(p: &[f64]) -> f64 {
+    let lead = p[p.len() - 1];
+    1. + p[..p.len() - 1]
+        .iter()
+        .fold(0., |max: f64, c| max.max((c / lead).abs()))
+}
+
+// splits (lo, hi] until each piece holds one root, then narrows it down
+// depth stops the splitting if rounding makes the sturm counts disagree
+fn isolate(p: &[f64], sequence: &[Vec<f64>], lo: f64, hi: f64, depth: u32, roots: &mut Vec<f64>) {
+    let count = sign_changes(sequence, lo).saturating_sub(sign_changes(sequence, hi));
+    if count == 0 {
+        return;
+    }
+
+    let mid = (lo + hi) / 2.;
+    if count == 1 || depth > 100 || mid <= lo || mid >= hi {
+        roots.push(bisect(|x| eval(p, x), lo, hi));
+        return;
+    }
+
+    isolate(p, sequence, lo, mid, depth + 1, roots);
+    isolate(p, sequence, mid, hi, depth + 1, roots);
+}
+
+// real roots of a polynomial without repeated roots, so each one is a sign change
+fn distinct_roots(p: &[f64]) -> Vec<f64> {
+    if p.len() <= 1 {
+        return vec![];
+    }
+
+    let bound = root_bound(p);
+    let sequence = sturm_sequence(p);
+    let mut roots = Vec::new();
+    // start just outside the bound so a root on it is still in (lo, hi]
+    isolate(p, &sequence, -bound * 1.01, bound, 0, &mut roots);
+
+    roots
+}
+
+// narrows a bracketed root down to f64 precision
+fn bisect(f: impl Fn(f64) -> f64, mut lo: f64, mut hi: f64) -> f64 {
+    let mut f_lo = f(lo);
+    if f(hi) == 0. {
+        return hi;
+    }
+
+    for _ in 0..200 {
+        let mid = (lo + hi) / 2.;
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        let f_mid = f(mid);
+        if f_mid == 0. {
+            return mid;
+        }
+        if (f_mid < 0.) == (f_lo < 0.) {
+            lo = mid;
+            f_lo = f_mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    (lo + hi) / 2.
+}
+
+// all real roots, sorted, with their multiplicities
+pub fn real_roots(p: &[f64]) -> Vec<(f64, u32)> {
+    let p = trim(p.to_vec());
+    if p.len() <= 1 {
+        return vec![];
+    }
+
+    // each gcd with the derivative takes one off the multiplicity of every root
+    let mut chain = vec![monic(&p)];
+    while let Some(g) = chain.last().filter(|g| g.len() > 1) {
+        let next = gcd(g, &derivative(g));
+        chain.push(next);
+    }
+
+    // dividing neighbours in the chain leaves the roots with multiplicity of at least k, each once
+    let levels: Vec<Vec<f64>> = chain
+        .windows(2)
+        .map(|pair| distinct_roots(&div_rem(&pair[0], &pair[1]).0))
+        .collect();
+
+    let close = |a: f64, b: f64| (a - b).abs() <= 1e-3 * a.abs().max(1.);
+    let mut roots: Vec<(f64, u32)> = levels[0]
+        .iter()
+        .map(|&x| {
+            let multiplicity = levels
+                .iter()
+                .filter(|level| level.iter().any(|&y| close(x, y)))
+                .count();
+            (polish(&p, x, multiplicity as u32), multiplicity as u32)
+        })
+        .collect();
+
+    roots.sort_by(|a, b| a.0.total_cmp(&b.0));
+    roots
+}
+
+// the divisions leave some rounding in the roots, so a few newton steps on the original
+// polynomial tidy them up, scaled by the multiplicity so repeated roots still converge
+fn polish(p: &[f64], mut x: f64, multiplicity: u32) -> f64 {
+    let dp = derivative(p);
+
+    for _ in 0..8 {
+        let y = eval(p, x);
+        let slope = eval(&dp, x);
+        if y == 0. || slope == 0. {
+            break;
+        }
+
+        let next = x - multiplicity as f64 * y / slope;
+        let moved_too_far = (next - x).abs() > 1e-3 * x.abs().max(1.);
+        if moved_too_far || eval(p, next).abs() >= y.abs() {
+            break;
+        }
+        x = next;
+    }
+
+    x
+}
+
+// roots of any function in [a, b], found from sign changes between samples
+// can't see roots that touch zero without crossing it, and skips sign changes through poles
+// a run of samples that are exactly zero is one root in the middle of it, eg: where (x-1)^300 underflows
+pub fn sampled_roots(f: impl Fn(f64) -> f64, a: f64, b: f64, samples: usize) -> Vec<f64> {
+    let step = (b - a) / samples as f64;
+    let mut roots = Vec::new();
+
+    let mut prev_x = a;
+    let mut prev_y = f(a);
+    // where the current run of zeros started
+    let mut zeros_from = (prev_y == 0.).then_some(a);
+
+    for i in 1..=samples {
+        let x = a + i as f64 * step;
+        let y = f(x);
+
+        if y == 0. {
+            zeros_from.get_or_insert(x);
+        } else if let Some(from) = zeros_from.take() {
+            roots.push((from + prev_x) / 2.);
+        } else if y.is_finite() && prev_y.is_finite() && (y < 0.) != (prev_y < 0.) {
+            let root = bisect(&f, prev_x, x);
+            // at a pole the function blows up instead of getting closer to zero
+            if f(root).abs() <= prev_y.abs().min(y.abs()) {
+                roots.push(root);
+            }
+        }
+
+        prev_x = x;
+        prev_y = y;
+    }
+    if let Some(from) = zeros_from {
+        roots.push((from + prev_x) / 2.);
+    }
+
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_roots(actual: Vec<(f64, u32)>, expected: &[(f64, u32)]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for ((x, m), (ex, em)) in actual.iter().zip(expected) {
+            assert!((x - ex).abs() < 1e-6, "{:?}", actual);
+            assert_eq!(m, em, "{:?}", actual);
+        }
+    }
+
+    #[test]
+    fn finds_simple_roots() {
+        // (x-1)(x+2)(x-3) = x^3 - 2x^2 - 5x + 6
+        let p = [6., -5., -2., 1.];
+
+        assert_roots(real_roots(&p), &[(-2., 1), (1., 1), (3., 1)]);
+    }
+
+    #[test]
+    fn finds_multiplicities() {
+        // (x-1)^2 (x+2)^3 x = x^6 + 4x^5 + x^4 - 10x^3 - 4x^2 + 8x
+        let p = [0., 8., -4., -10., 1., 4., 1.];
+
+        assert_roots(real_roots(&p), &[(-2., 3), (0., 1), (1., 2)]);
+    }
+
+    #[test]
+    fn ignores_complex_roots() {
+        // (x^2 + 1)(x - 0.5)
+        let p = [-0.5, 1., -0.5, 1.];
+
+        assert_roots(real_roots(&p), &[(0.5, 1)]);
+        assert!(real_roots(&[1., 0., 1.]).is_empty());
+    }
+
+    #[test]
+    fn sampled_roots_skip_poles() {
+        let roots = sampled_roots(|x| x.tan(), -2., 2., 100);
+
+        assert_eq!(roots.len(), 1);
+        assert!(roots[0].abs() < 1e-9);
+    }
+
+    #[test]
+    fn zeros_in_a_row_are_one_root() {
+        // flat at zero on [-0.5, 0.5], like a high power that underflows
+        let flat = |x: f64| if x.abs() <= 0.5 { 0. } else { x };
+        let roots = sampled_roots(flat, -2., 2., 100);
+        assert_eq!(roots.len(), 1);
+        assert!(roots[0].abs() < 1e-9);
+
+        // a single zero sample is still a root on it
+        assert_eq!(sampled_roots(|x| x - 1., 0., 2., 10), vec![1.]);
+    }
+}
@@ -0,0 +1,235 @@
+// what the graph draws with, kept apart from any one renderer
+// so the same drawing can go to a window, a file or be checked in tests
+pub mod canvas;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod raster;
+pub mod recording;
+pub mod svg;
+
+// the font everything is drawn in, embedded so text looks the same everywhere
+pub const FONT: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans.ttf");
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b, a: 255 }
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color { r, g, b, a }
+    }
+
+    pub const fn white() -> Self {
+        Color::rgb(255, 255, 255)
+    }
+
+    pub const fn black() -> Self {
+        Color::rgb(0, 0, 0)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+}
+
+// where text sits horizontally around its x
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+}
+
+// where text sits vertically around its y
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Baseline {
+    Top,
+    Middle,
+    #[default]
+    Alphabetic,
+}
+
+// how a path or text is filled or stroked, built up like femtovg's paint
+#[derive(Debug, Clone, PartialEq)]
+pub struct Paint {
+    pub color: Color,
+    pub line_width: f32,
+    pub line_cap: LineCap,
+    pub font_size: f32,
+    pub text_align: Align,
+    pub text_baseline: Baseline,
+}
+
+impl Paint {
+    pub fn color(color: Color) -> Self {
+        Paint {
+            color,
+            line_width: 1.,
+            line_cap: LineCap::default(),
+            font_size: 16.,
+            text_align: Align::default(),
+            text_baseline: Baseline::default(),
+        }
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_line_width(mut self, width: f32) -> Self {
+        self.line_width = width;
+        self
+    }
+
+    pub fn with_line_cap(mut self, cap: LineCap) -> Self {
+        self.line_cap = cap;
+        self
+    }
+
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    pub fn with_text_align(mut self, align: Align) -> Self {
+        self.text_align = align;
+        self
+    }
+
+    pub fn with_text_baseline(mut self, baseline: Baseline) -> Self {
+        self.text_baseline = baseline;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    // control point, then end point
+    QuadTo(f32, f32, f32, f32),
+    // both control points, then end point
+    BezierTo(f32, f32, f32, f32, f32, f32),
+    Close,
+}
+
+// a list of commands every surface can draw, circles and rounded corners are made of beziers
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Path {
+    commands: Vec<PathCommand>,
+}
+
+// how far along the tangents the control points of a quarter circle are, as a fraction of the radius
+const KAPPA: f32 = 0.552_284_8;
+
+impl Path {
+    pub fn new() -> Self {
+        Path::default()
+    }
+
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+
+    pub fn move_to(&mut self, x: f32, y: f32) {
+        self.commands.push(PathCommand::MoveTo(x, y));
+    }
+
+    pub fn line_to(&mut self, x: f32, y: f32) {
+        self.commands.push(PathCommand::LineTo(x, y));
+    }
+
+    pub fn quad_to(&mut self, cx: f32, cy: f32, x: f32, y: f32) {
+        self.commands.push(PathCommand::QuadTo(cx, cy, x, y));
+    }
+
+    pub fn bezier_to(&mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) {
+        self.commands
+            .push(PathCommand::BezierTo(c1x, c1y, c2x, c2y, x, y));
+    }
+
+    pub fn close(&mut self) {
+        self.commands.push(PathCommand::Close);
+    }
+
+    // a rounded rect that's all corners, starting from the top
+    pub fn circle(&mut self, cx: f32, cy: f32, r: f32) {
+        self.rounded_rect(cx - r, cy - r, 2. * r, 2. * r, r);
+    }
+
+    // clockwise from the end of the top left corner
+    pub fn rounded_rect(&mut self, x: f32, y: f32, w: f32, h: f32, r: f32) {
+        let r = r.min(w / 2.).min(h / 2.);
+        let k = r * (1. - KAPPA);
+        let (right, bottom) = (x + w, y + h);
+
+        self.move_to(x + r, y);
+        self.line_to(right - r, y);
+        self.bezier_to(right - k, y, right, y + k, right, y + r);
+        self.line_to(right, bottom - r);
+        self.bezier_to(right, bottom - k, right - k, bottom, right - r, bottom);
+        self.line_to(x + r, bottom);
+        self.bezier_to(x + k, bottom, x, bottom - k, x, bottom - r);
+        self.line_to(x, y + r);
+        self.bezier_to(x, y + k, x + k, y, x + r, y);
+        self.close();
+    }
+}
+
+// something the graph can be drawn on
+pub trait Surface {
+    fn fill_path(&mut self, path: &Path, paint: &Paint);
+    fn stroke_path(&mut self, path: &Path, paint: &Paint);
+    // text at x, y placed by the paint's align and baseline
+    fn fill_text(&mut self, x: f32, y: f32, text: &str, paint: &Paint);
+    // how wide the text would be in pixels, for laying out labels
+    fn measure_text(&self, text: &str, paint: &Paint) -> f32;
+}
+
+// roughly how wide a character of DejaVu Sans is, as a fraction of the font size
+const CHARACTER_WIDTH: f32 = 0.6;
+
+// for surfaces without a font to measure with, every character is taken to be the same width
+pub fn approximate_text_width(text: &str, paint: &Paint) -> f32 {
+    text.chars().count() as f32 * paint.font_size * CHARACTER_WIDTH
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circles_are_closed_beziers() {
+        let mut path = Path::new();
+        path.circle(10., 20., 5.);
+
+        let commands = path.commands();
+        assert_eq!(commands[0], PathCommand::MoveTo(10., 15.));
+        assert_eq!(commands.last(), Some(&PathCommand::Close));
+
+        let beziers = commands
+            .iter()
+            .filter(|c| matches!(c, PathCommand::BezierTo(..)))
+            .count();
+        assert_eq!(beziers, 4);
+        // the first quarter ends at the right of the circle, leaving the top horizontally
+        match commands[2] {
+            PathCommand::BezierTo(c1x, c1y, _, _, x, y) => {
+                assert!((c1x - (10. + 5. * KAPPA)).abs() < 1e-5 && c1y == 15.);
+                assert_eq!((x, y), (15., 20.));
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use femtovg::{Canvas, Renderer};
+
+use super::{Align, Baseline, Color, LineCap, Paint, Path, PathCommand, Surface};
+
+// draws with femtovg, onto the window or the browser canvas
+impl<T: Renderer> Surface for Canvas<T> {
+    fn fill_path(&mut self, path: &Path, paint: &Paint) {
+        Canvas::fill_path(self, &to_femtovg_path(path), &to_femtovg_paint(paint));
+    }
+
+    fn stroke_path(&mut self, path: &Path, paint: &Paint) {
+        Canvas::stroke_path(self, &to_femtovg_path(path), &to_femtovg_paint(paint));
+    }
+
+    fn fill_text(&mut self, x: f32, y: f32, text: &str, paint: &Paint) {
+        // only fails if no font has been loaded, the graph is still useful without text
+        let _ = Canvas::fill_text(self, x, y, text, &to_femtovg_paint(paint));
+    }
+
+    fn measure_text(&self, text: &str, paint: &Paint) -> f32 {
+        Canvas::measure_text(self, 0., 0., text, &to_femtovg_paint(paint))
+            .map(|metrics| metrics.width())
+            .unwrap_or(0.)
+    }
+}
+
+fn to_femtovg_path(path: &Path) -> femtovg::Path {
+    let mut femtovg_path = femtovg::Path::new();
+    for command in path.commands() {
+        match *command {
+            PathCommand::MoveTo(x, y) => femtovg_path.move_to(x, y),
+            PathCommand::LineTo(x, y) => femtovg_path.line_to(x, y),
+            PathCommand::QuadTo(cx, cy, x, y) => femtovg_path.quad_to(cx, cy, x, y),
+            PathCommand::BezierTo(c1x, c1y, c2x, c2y, x, y) => {
+                femtovg_path.bezier_to(c1x, c1y, c2x, c2y, x, y)
+            }
+            PathCommand::Close => femtovg_path.close(),
+        }
+    }
+    femtovg_path
+}
+
+fn to_femtovg_paint(paint: &Paint) -> femtovg::Paint {
+    let Color { r, g, b, a } = paint.color;
+    let cap = match paint.line_cap {
+        LineCap::Butt => femtovg::LineCap::Butt,
+        LineCap::Round => femtovg::LineCap::Round,
+    };
+    let align = match paint.text_align {
+        Align::Left => femtovg::Align::Left,
+        Align::Center => femtovg::Align::Center,
+    };
+    let baseline = match paint.text_baseline {
+        Baseline::Top => femtovg::Baseline::Top,
+        Baseline::Middle => femtovg::Baseline::Middle,
+        Baseline::Alphabetic => femtovg::Baseline::Alphabetic,
+    };
+
+    femtovg::Paint::color(femtovg::Color::rgba(r, g, b, a))
+        .with_line_width(paint.line_width)
+        .with_line_cap(cap)
+        .with_font_size(paint.font_size)
+        .with_text_align(align)
+        .with_text_baseline(baseline)
+}
@@ -0,0 +1,242 @@
+use tiny_skia::{FillRule, PathBuilder, Pixmap, Stroke, Transform};
+use ttf_parser::{Face, OutlineBuilder};
+
+use super::{Align, Baseline, Color, LineCap, Paint, Path, PathCommand, Surface, FONT};
+
+// draws into an image in memory with a software rasterizer, so it needs no gpu or display
+// text is drawn from the glyph outlines of the same font the window uses
+pub struct Raster {
+    pixmap: Pixmap,
+    // device pixels per logical pixel, eg: 2 for an image twice as sharp
+    pixel_ratio: f32,
+    face: Face<'static>,
+}
+
+impl Raster {
+    // none when the image would have no pixels
+    pub fn new(width: f32, height: f32, pixel_ratio: f32, background: Color) -> Option<Self> {
+        let mut pixmap = Pixmap::new(
+            (width * pixel_ratio).round() as u32,
+            (height * pixel_ratio).round() as u32,
+        )?;
+        pixmap.fill(to_skia_color(background));
+
+        Some(Raster {
+            pixmap,
+            pixel_ratio,
+            face: Face::parse(FONT, 0).expect("the bundled font is valid"),
+        })
+    }
+
+    pub fn encode_png(&self) -> Vec<u8> {
+        // only writes to memory, which can't fail
+        self.pixmap.encode_png().expect("failed to encode the png")
+    }
+
+    fn transform(&self) -> Transform {
+        Transform::from_scale(self.pixel_ratio, self.pixel_ratio)
+    }
+
+    // how far the baseline is below y for the paint's baseline, in pixels
+    fn baseline_offset(&self, paint: &Paint) -> f32 {
+        let units = self.font_scale(paint);
+        let (ascender, descender) = (self.face.ascender() as f32, self.face.descender() as f32);
+
+        match paint.text_baseline {
+            Baseline::Top => ascender * units,
+            Baseline::Middle => (ascender + descender) / 2. * units,
+            Baseline::Alphabetic => 0.,
+        }
+    }
+
+    // pixels per font unit
+    fn font_scale(&self, paint: &Paint) -> f32 {
+        paint.font_size / self.face.units_per_em() as f32
+    }
+}
+
+impl Surface for Raster {
+    fn fill_path(&mut self, path: &Path, paint: &Paint) {
+        if let Some(path) = to_skia_path(path) {
+            let transform = self.transform();
+            self.pixmap.fill_path(
+                &path,
+                &to_skia_paint(paint.color),
+                FillRule::Winding,
+                transform,
+                None,
+            );
+        }
+    }
+
+    fn stroke_path(&mut self, path: &Path, paint: &Paint) {
+        if let Some(path) = to_skia_path(path) {
+            let stroke = Stroke {
+                width: paint.line_width,
+                line_cap: match paint.line_cap {
+                    LineCap::Butt => tiny_skia::LineCap::Butt,
+                    LineCap::Round => tiny_skia::LineCap::Round,
+                },
+                ..Stroke::default()
+            };
+            let transform = self.transform();
+            self.pixmap
+                .stroke_path(&path, &to_skia_paint(paint.color), &stroke, transform, None);
+        }
+    }
+
+    fn fill_text(&mut self, x: f32, y: f32, text: &str, paint: &Paint) {
+        let units = self.font_scale(paint);
+        let mut pen_x = match paint.text_align {
+            Align::Left => x,
+            Align::Center => x - self.measure_text(text, paint) / 2.,
+        };
+        let baseline = y + self.baseline_offset(paint);
+
+        let mut outlines = Outlines {
+            builder: PathBuilder::new(),
+            origin: (pen_x, baseline),
+            units,
+        };
+        for c in text.chars() {
+            let Some(glyph) = self.face.glyph_index(c) else {
+                continue;
+            };
+            outlines.origin = (pen_x, baseline);
+            self.face.outline_glyph(glyph, &mut outlines);
+            pen_x += self.face.glyph_hor_advance(glyph).unwrap_or(0) as f32 * units;
+        }
+
+        if let Some(path) = outlines.builder.finish() {
+            let transform = self.transform();
+            self.pixmap.fill_path(
+                &path,
+                &to_skia_paint(paint.color),
+                FillRule::Winding,
+                transform,
+                None,
+            );
+        }
+    }
+
+    fn measure_text(&self, text: &str, paint: &Paint) -> f32 {
+        let units = self.font_scale(paint);
+        text.chars()
+            .filter_map(|c| self.face.glyph_index(c))
+            .map(|glyph| self.face.glyph_hor_advance(glyph).unwrap_or(0) as f32 * units)
+            .sum()
+    }
+}
+
+// collects glyph outlines into one path, font units are y up from the pen so they're flipped
+struct Outlines {
+    builder: PathBuilder,
+    origin: (f32, f32),
+    units: f32,
+}
+
+impl Outlines {
+    fn point(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            self.origin.0 + x * self.units,
+            self.origin.1 - y * self.units,
+        )
+    }
+}
+
+impl OutlineBuilder for Outlines {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.builder.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.builder.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x, y) = self.point(x, y);
+        self.builder.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x2, y2) = self.point(x2, y2);
+        let (x, y) = self.point(x, y);
+        self.builder.cubic_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
+
+// none for an empty path, or one tiny-skia can't draw, eg: with coordinates that aren't finite
+fn to_skia_path(path: &Path) -> Option<tiny_skia::Path> {
+    let mut builder = PathBuilder::new();
+    for command in path.commands() {
+        match *command {
+            PathCommand::MoveTo(x, y) => builder.move_to(x, y),
+            PathCommand::LineTo(x, y) => builder.line_to(x, y),
+            PathCommand::QuadTo(cx, cy, x, y) => builder.quad_to(cx, cy, x, y),
+            PathCommand::BezierTo(c1x, c1y, c2x, c2y, x, y) => {
+                builder.cubic_to(c1x, c1y, c2x, c2y, x, y)
+            }
+            PathCommand::Close => builder.close(),
+        }
+    }
+    builder.finish()
+}
+
+fn to_skia_color(color: Color) -> tiny_skia::Color {
+    tiny_skia::Color::from_rgba8(color.r, color.g, color.b, color.a)
+}
+
+fn to_skia_paint(color: Color) -> tiny_skia::Paint<'static> {
+    let mut paint = tiny_skia::Paint::default();
+    paint.set_color(to_skia_color(color));
+    paint.anti_alias = true;
+    paint
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(raster: &Raster, x: u32, y: u32) -> (u8, u8, u8) {
+        let pixel = raster.pixmap.pixel(x, y).unwrap().demultiply();
+        (pixel.red(), pixel.green(), pixel.blue())
+    }
+
+    #[test]
+    fn strokes_and_text_are_rasterized() {
+        let mut raster = Raster::new(100., 50., 2., Color::black()).unwrap();
+        assert_eq!((raster.pixmap.width(), raster.pixmap.height()), (200, 100));
+
+        let mut line = Path::new();
+        line.move_to(0., 10.);
+        line.line_to(100., 10.);
+        raster.stroke_path(
+            &line,
+            &Paint::color(Color::rgb(255, 0, 0)).with_line_width(2.),
+        );
+        // in device pixels, so twice as far down
+        assert_eq!(rgb(&raster, 100, 20), (255, 0, 0));
+        assert_eq!(rgb(&raster, 100, 40), (0, 0, 0));
+
+        let paint = Paint::color(Color::white())
+            .with_font_size(20.)
+            .with_text_baseline(Baseline::Top);
+        let width = raster.measure_text("88", &paint);
+        assert!(width > 15. && width < 30., "{}", width);
+
+        raster.fill_text(10., 20., "88", &paint);
+        let lit = (20..20 + 2 * width as u32)
+            .flat_map(|x| (40..80).map(move |y| (x, y)))
+            .filter(|(x, y)| rgb(&raster, *x, *y).0 > 128)
+            .count();
+        assert!(lit > 100, "{}", lit);
+    }
+}
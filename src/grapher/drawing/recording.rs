@@ -0,0 +1,49 @@
+use super::{approximate_text_width, Paint, Path, Surface};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCall {
+    Fill(Path, Paint),
+    Stroke(Path, Paint),
+    Text {
+        x: f32,
+        y: f32,
+        text: String,
+        paint: Paint,
+    },
+}
+
+// keeps everything drawn on it in order, so a graph can be checked without a window
+#[derive(Debug, Default)]
+pub struct Recording {
+    pub calls: Vec<DrawCall>,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Recording::default()
+    }
+}
+
+impl Surface for Recording {
+    fn fill_path(&mut self, path: &Path, paint: &Paint) {
+        self.calls.push(DrawCall::Fill(path.clone(), paint.clone()));
+    }
+
+    fn stroke_path(&mut self, path: &Path, paint: &Paint) {
+        self.calls
+            .push(DrawCall::Stroke(path.clone(), paint.clone()));
+    }
+
+    fn fill_text(&mut self, x: f32, y: f32, text: &str, paint: &Paint) {
+        self.calls.push(DrawCall::Text {
+            x,
+            y,
+            text: String::from(text),
+            paint: paint.clone(),
+        });
+    }
+
+    fn measure_text(&self, text: &str, paint: &Paint) -> f32 {
+        approximate_text_width(text, paint)
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt::Write;
+
+use super::{
+    approximate_text_width, Align, Baseline, Color, LineCap, Paint, Path, PathCommand, Surface,
+};
+
+// builds an svg document, paths stay as lines and beziers so it prints sharp at any size
+pub struct Svg {
+    width: f32,
+    height: f32,
+    background: Color,
+    elements: String,
+}
+
+impl Svg {
+    pub fn new(width: f32, height: f32, background: Color) -> Self {
+        Svg {
+            width,
+            height,
+            background,
+            elements: String::new(),
+        }
+    }
+
+    // the whole document, with everything drawn so far over the background
+    pub fn finish(self) -> String {
+        let (width, height) = (number(self.width), number(self.height));
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n\
+             <rect width=\"100%\" height=\"100%\"{}/>\n\
+             {}</svg>\n",
+            color_attributes("fill", self.background),
+            self.elements
+        )
+    }
+}
+
+impl Surface for Svg {
+    fn fill_path(&mut self, path: &Path, paint: &Paint) {
+        let _ = writeln!(
+            self.elements,
+            "<path d=\"{}\"{}/>",
+            path_data(path),
+            color_attributes("fill", paint.color)
+        );
+    }
+
+    fn stroke_path(&mut self, path: &Path, paint: &Paint) {
+        let cap = match paint.line_cap {
+            LineCap::Butt => "",
+            LineCap::Round => " stroke-linecap=\"round\"",
+        };
+        let _ = writeln!(
+            self.elements,
+            "<path d=\"{}\" fill=\"none\"{} stroke-width=\"{}\"{}/>",
+            path_data(path),
+            color_attributes("stroke", paint.color),
+            number(paint.line_width),
+            cap
+        );
+    }
+
+    fn fill_text(&mut self, x: f32, y: f32, text: &str, paint: &Paint) {
+        let anchor = match paint.text_align {
+            Align::Left => "start",
+            Align::Center => "middle",
+        };
+        let baseline = match paint.text_baseline {
+            Baseline::Top => "hanging",
+            Baseline::Middle => "central",
+            Baseline::Alphabetic => "alphabetic",
+        };
+        let _ = writeln!(
+            self.elements,
+            "<text x=\"{}\" y=\"{}\" font-family=\"DejaVu Sans, sans-serif\" font-size=\"{}\" \
+             text-anchor=\"{anchor}\" dominant-baseline=\"{baseline}\"{}>{}</text>",
+            number(x),
+            number(y),
+            number(paint.font_size),
+            color_attributes("fill", paint.color),
+            escape(text)
+        );
+    }
+
+    // the viewer picks the font, so the width can only be estimated
+    fn measure_text(&self, text: &str, paint: &Paint) -> f32 {
+        approximate_text_width(text, paint)
+    }
+}
+
+fn path_data(path: &Path) -> String {
+    let mut data = String::new();
+    for command in path.commands() {
+        let (letter, values) = match *command {
+            PathCommand::MoveTo(x, y) => ('M', vec![x, y]),
+            PathCommand::LineTo(x, y) => ('L', vec![x, y]),
+            PathCommand::QuadTo(cx, cy, x, y) => ('Q', vec![cx, cy, x, y]),
+            PathCommand::BezierTo(c1x, c1y, c2x, c2y, x, y) => {
+                ('C', vec![c1x, c1y, c2x, c2y, x, y])
+            }
+            PathCommand::Close => ('Z', vec![]),
+        };
+        data.push(letter);
+        let values: Vec<String> = values.into_iter().map(number).collect();
+        data.push_str(&values.join(" "));
+    }
+    data
+}
+
+// eg: fill="rgb(255,0,0)" with fill-opacity only when it's see through
+fn color_attributes(name: &str, color: Color) -> String {
+    let Color { r, g, b, a } = color;
+    let mut attributes = format!(" {name}=\"rgb({r},{g},{b})\"");
+    if a < 255 {
+        let _ = write!(
+            attributes,
+            " {name}-opacity=\"{}\"",
+            number(a as f32 / 255.)
+        );
+    }
+    attributes
+}
+
+// two decimal places is a hundredth of a pixel, without trailing zeros to keep files small
+fn number(value: f32) -> String {
+    let formatted = format!("{:.2}", value);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+
+    match trimmed {
+        "-0" => String::from("0"),
+        _ => String::from(trimmed),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_and_text_become_svg_elements() {
+        let mut svg = Svg::new(100., 50., Color::black());
+
+        let mut path = Path::new();
+        path.move_to(0., 0.5);
+        path.quad_to(10., 20., 30.25, 40.);
+        path.bezier_to(1., 2., 3., 4., 5., 6.);
+        path.close();
+        svg.stroke_path(&path, &Paint::color(Color::rgba(255, 0, 0, 51)));
+        svg.fill_text(
+            5.,
+            6.,
+            "x < 1 & y > 2",
+            &Paint::color(Color::white()).with_text_align(Align::Center),
+        );
+
+        let document = svg.finish();
+        assert!(document.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\""));
+        assert!(document.contains(
+            "<path d=\"M0 0.5Q10 20 30.25 40C1 2 3 4 5 6Z\" fill=\"none\" \
+             stroke=\"rgb(255,0,0)\" stroke-opacity=\"0.2\" stroke-width=\"1\"/>"
+        ));
+        assert!(document.contains("text-anchor=\"middle\""));
+        assert!(document.contains(">x &lt; 1 &amp; y &gt; 2</text>"));
+        assert!(document.ends_with("</svg>\n"));
+    }
+}
@@ -1,21 +1,25 @@
+use std::collections::HashMap;
 use std::fmt::{Debug, Display};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
 
-#[derive(Debug)]
+use super::analysis;
+use super::expression::Expression;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Point {
     pub x: f32,
     pub y: f32,
 }
 
-impl Point {
-    pub fn from_ints(x: i32, y: i32) -> Self {
-        Point {
-            x: x as f32,
-            y: y as f32,
-        }
-    }
+// a real x where the equation is 0, multiplicity is how many times the factor repeats
+// eg: (x-1)^2 has a root at 1 with multiplicity 2
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Root {
+    pub x: f32,
+    pub multiplicity: u32,
 }
 
-#[derive(Default, Debug, PartialEq)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct Polynomial {
     terms: Vec<Term>,
     precision: u32,
@@ -31,6 +35,133 @@ impl Polynomial {
         }
     }
 
+    pub fn constant(c: f32) -> Self {
+        Polynomial::new(vec![Term::new(c, 0)])
+    }
+    pub fn x() -> Self {
+        Polynomial::new(vec![Term::x_to_the(1)])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.terms.iter().all(|t| t.c == 0.)
+    }
+
+    // the value if there's no x in it, eg: the 3 in (x+1)^3
+    pub fn as_constant(&self) -> Option<f32> {
+        if self.terms.iter().all(|t| t.power == 0 || t.c == 0.) {
+            Some(self.calc(0.))
+        } else {
+            None
+        }
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.terms.iter().all(|t| t.c.is_finite())
+    }
+
+    // the highest power with a coefficient, 0 for constants
+    pub fn degree(&self) -> u32 {
+        self.terms
+            .iter()
+            .filter(|t| t.c != 0.)
+            .map(|t| t.power.max(0) as u32)
+            .max()
+            .unwrap_or(0)
+    }
+
+    // adds up like terms in the order they first appear, so the input order is kept
+    // no rounding here unlike simplify, so repeated arithmetic doesn't lose precision
+    // the index of each power is kept so big products don't search for their like terms
+    fn combined(terms: impl Iterator<Item = Term>) -> Self {
+        let mut combined = Vec::<Term>::new();
+        let mut index_of_power = HashMap::<i32, usize>::new();
+        for term in terms {
+            match index_of_power.get(&term.power) {
+                Some(&i) => combined[i].c += term.c,
+                None => {
+                    index_of_power.insert(term.power, combined.len());
+                    combined.push(term);
+                }
+            }
+        }
+        combined.retain(|t| t.c != 0.);
+
+        Polynomial::new(combined)
+    }
+
+    // highest power first, leaving out the zeros
+    fn from_dense(coefficients: &[f64]) -> Self {
+        let terms = coefficients
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, c)| **c != 0.)
+            .map(|(power, c)| Term::new(*c as f32, power as i32))
+            .collect();
+
+        Polynomial::new(terms)
+    }
+
+    // polynomial long division, returns (quotient, remainder)
+    // panics on the zero polynomial like integer division does on 0
+    pub fn div_rem(&self, divisor: &Polynomial) -> (Polynomial, Polynomial) {
+        assert!(!divisor.is_zero(), "division by the zero polynomial");
+
+        // dividing by a constant just scales, keeping the term order
+        if let Some(c) = divisor.as_constant() {
+            let quotient = self.terms.iter().map(|t| Term::new(t.c / c, t.power));
+            return (Polynomial::combined(quotient), Polynomial::new(vec![]));
+        }
+
+        let (quotient, remainder) =
+            analysis::div_rem(&self.dense_coefficients(), &divisor.dense_coefficients());
+        (
+            Polynomial::from_dense(&quotient),
+            Polynomial::from_dense(&remainder),
+        )
+    }
+
+    // monic greatest common divisor, eg: gcd(2x^2 - 2, 3x - 3) = x - 1
+    pub fn gcd(&self, other: &Polynomial) -> Polynomial {
+        Polynomial::from_dense(&analysis::gcd(
+            &self.dense_coefficients(),
+            &other.dense_coefficients(),
+        ))
+    }
+
+    // exponentiation by squaring, so (x+1)^20 doesn't need 20 multiplications
+    pub fn pow(&self, mut n: u32) -> Polynomial {
+        let mut result = Polynomial::constant(1.);
+        let mut square = self.clone();
+
+        while n > 0 {
+            if n % 2 == 1 {
+                result = &result * &square;
+            }
+            n /= 2;
+            if n > 0 {
+                square = &square * &square;
+            }
+        }
+
+        result
+    }
+
+    // self(inner(x)), using horner's method so each power of inner is only built once
+    pub fn compose(&self, inner: &Polynomial) -> Polynomial {
+        self.dense_coefficients()
+            .iter()
+            .rev()
+            .fold(Polynomial::new(vec![]), |acc, c| {
+                &(&acc * inner) + &Polynomial::constant(*c as f32)
+            })
+    }
+
+    // like terms added up without rounding, so coefficients stay as they were typed
+    pub fn collect_like_terms(&self) -> Self {
+        Polynomial::combined(self.terms.iter().cloned())
+    }
+
     pub fn simplify(&self) -> Self {
         let mut simplified_terms = Vec::<Term>::new();
 
@@ -48,36 +179,223 @@ impl Polynomial {
 
             let rounding_factor = 10_i32.pow(self.precision) as f32;
             let rounded_c = (combined_c * rounding_factor).round() / rounding_factor;
+            if rounded_c == 0. {
+                // like terms cancelled out
+                continue;
+            }
             simplified_terms.push(Term::new(rounded_c, term.power));
         }
 
         Polynomial::new(simplified_terms)
     }
 
-    fn _find_term(&self, power: i32) -> Term {
-        let term_opt = self.terms.iter().find(|t| t.power == power);
-        match term_opt {
-            None => Term::new(0., power),
-            Some(term) => Term::new(term.c, term.power),
+    // power rule on each term, constants become 0 and are dropped
+    // not simplified, rounding would move the curve and its roots when coefficients are small
+    pub fn derivative(&self) -> Self {
+        let terms = self
+            .terms
+            .iter()
+            .map(|t| Term::new(t.c * t.power as f32, t.power - 1));
+
+        Polynomial::combined(terms)
+    }
+
+    pub fn nth_derivative(&self, n: u32) -> Self {
+        let start = Polynomial::combined(self.terms.iter().cloned());
+        (0..n).fold(start, |p, _| p.derivative())
+    }
+
+    // antiderivative with a constant of 0
+    // not simplified, rounding would throw away precision from the division
+    pub fn integral(&self) -> Self {
+        let terms = self
+            .terms
+            .iter()
+            .map(|t| Term::new(t.c / (t.power + 1) as f32, t.power + 1))
+            .collect();
+
+        Polynomial::new(terms)
+    }
+
+    pub fn definite_integral(&self, a: f32, b: f32) -> f32 {
+        let antiderivative = self.integral();
+        antiderivative.calc(b) - antiderivative.calc(a)
+    }
+
+    // coefficients indexed by power, for the numeric algorithms in analysis
+    // negative powers aren't polynomial terms, the parser and builder never make them
+    fn dense_coefficients(&self) -> Vec<f64> {
+        let degree = self.terms.iter().map(|t| t.power).max().unwrap_or(0).max(0);
+        let mut coefficients = vec![0.; degree as usize + 1];
+
+        for term in self.terms.iter().filter(|t| t.power >= 0) {
+            coefficients[term.power as usize] += term.c as f64;
         }
+
+        coefficients
+    }
+
+    // no value on [a, b] is bigger than this, from adding up the biggest each term can be
+    fn bound_on(&self, a: f32, b: f32) -> f32 {
+        let furthest = a.abs().max(b.abs());
+        self.terms
+            .iter()
+            .map(|t| t.c.abs() * furthest.powi(t.power))
+            .sum()
+    }
+
+    // every real root, in increasing order
+    pub fn real_roots(&self) -> Vec<Root> {
+        analysis::real_roots(&self.dense_coefficients())
+            .into_iter()
+            .map(|(x, multiplicity)| Root {
+                x: x as f32,
+                multiplicity,
+            })
+            .collect()
     }
 
-    pub fn _find_vertex(&self) -> Point {
-        let a = self._find_term(2).c;
-        let b = self._find_term(1).c;
+    // local minima and maxima, where the slope changes sign
+    // a root of the derivative with even multiplicity is a flat spot the curve passes through
+    pub fn extrema(&self) -> Vec<CriticalPoint> {
+        let slope = self.derivative();
+
+        slope
+            .real_roots()
+            .into_iter()
+            .filter(|root| root.multiplicity % 2 == 1)
+            .map(|root| {
+                // the first derivative past the slope that isn't zero here decides the direction
+                let curvature = self.nth_derivative(root.multiplicity + 1).calc(root.x);
+                let kind = if curvature > 0. {
+                    CriticalPointKind::LocalMinimum
+                } else {
+                    CriticalPointKind::LocalMaximum
+                };
+                CriticalPoint {
+                    kind,
+                    point: Point {
+                        x: root.x,
+                        y: self.calc(root.x),
+                    },
+                }
+            })
+            .collect()
+    }
 
-        let v_x = -b / (2. * a);
-        let v_y = self.calc(v_x);
+    // where the concavity changes, ie: the second derivative changes sign
+    pub fn inflection_points(&self) -> Vec<CriticalPoint> {
+        self.nth_derivative(2)
+            .real_roots()
+            .into_iter()
+            .filter(|root| root.multiplicity % 2 == 1)
+            .map(|root| CriticalPoint {
+                kind: CriticalPointKind::Inflection,
+                point: Point {
+                    x: root.x,
+                    y: self.calc(root.x),
+                },
+            })
+            .collect()
+    }
 
-        Point { x: v_x, y: v_y }
+    // extrema and inflection points together, in increasing x
+    pub fn critical_points(&self) -> Vec<CriticalPoint> {
+        let mut points = self.extrema();
+        points.extend(self.inflection_points());
+        points.sort_by(|a, b| a.point.x.total_cmp(&b.point.x));
+        points
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CriticalPointKind {
+    LocalMinimum,
+    LocalMaximum,
+    Inflection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CriticalPoint {
+    pub kind: CriticalPointKind,
+    pub point: Point,
+}
+
 // impl PartialEq for Polynomial
 // so that term order doesn't matter
 
 pub trait Calculate: Debug + Display {
     fn calc(&self, x: f32) -> f32;
+
+    // signed area between the curve and the x axis from a to b
+    // numeric by default using Simpson's rule, override when there's an exact answer
+    fn integrate(&self, a: f32, b: f32) -> f32 {
+        let intervals = 256; // must be even
+        let h = (b - a) / intervals as f32;
+
+        let mut sum = self.calc(a) + self.calc(b);
+        for i in 1..intervals {
+            let weight = if i % 2 == 1 { 4. } else { 2. };
+            sum += weight * self.calc(a + i as f32 * h);
+        }
+
+        sum * h / 3.
+    }
+
+    // dy/dx at x, numeric by default using a central difference
+    fn slope(&self, x: f32) -> f32 {
+        let h = 1e-3 * x.abs().max(1.);
+        (self.calc(x + h) - self.calc(x - h)) / (2. * h)
+    }
+
+    // how far the curve can get from the cubic through its ends with matching slopes on [a, b]
+    // None when there's no way to know, so the curve has to be sampled instead
+    fn cubic_error_bound(&self, _a: f32, _b: f32) -> Option<f32> {
+        None
+    }
+
+    // roots in [a, b], numeric by default so multiplicity isn't known and is reported as 1
+    fn roots_in(&self, a: f32, b: f32) -> Vec<Root> {
+        let samples = 1000;
+        analysis::sampled_roots(|x| self.calc(x as f32) as f64, a as f64, b as f64, samples)
+            .into_iter()
+            .map(|x| Root {
+                x: x as f32,
+                multiplicity: 1,
+            })
+            .collect()
+    }
+
+    // x values in [a, b] where the curve is at height y, numeric by default
+    fn solve(&self, y: f32, a: f32, b: f32) -> Vec<f32> {
+        let samples = 1000;
+        let shifted = |x: f64| (self.calc(x as f32) - y) as f64;
+        analysis::sampled_roots(shifted, a as f64, b as f64, samples)
+            .into_iter()
+            .map(|x| x as f32)
+            .collect()
+    }
+
+    // points where this and the other curve meet in [a, b], numeric by default
+    fn intersections_with(&self, other: &Self, a: f32, b: f32) -> Vec<Point>
+    where
+        Self: Sized,
+    {
+        numeric_intersections(self, other, a, b)
+    }
+}
+
+fn numeric_intersections<T: Calculate, U: Calculate>(f: &T, g: &U, a: f32, b: f32) -> Vec<Point> {
+    let samples = 1000;
+    let difference = |x: f64| (f.calc(x as f32) - g.calc(x as f32)) as f64;
+
+    analysis::sampled_roots(difference, a as f64, b as f64, samples)
+        .into_iter()
+        .map(|x| Point {
+            x: x as f32,
+            y: f.calc(x as f32),
+        })
+        .collect()
 }
 
 impl Calculate for Polynomial {
@@ -91,11 +409,59 @@ impl Calculate for Polynomial {
 
         sum
     }
+
+    fn slope(&self, x: f32) -> f32 {
+        self.terms
+            .iter()
+            .filter(|t| t.power != 0)
+            .map(|t| t.c * t.power as f32 * x.powi(t.power - 1))
+            .sum()
+    }
+
+    // the hermite interpolation error, max|f''''| (b - a)^4 / 384
+    fn cubic_error_bound(&self, a: f32, b: f32) -> Option<f32> {
+        let fourth_derivative = self.nth_derivative(4).bound_on(a, b);
+        Some(fourth_derivative * (b - a).powi(4) / 384.)
+    }
+
+    fn integrate(&self, a: f32, b: f32) -> f32 {
+        self.definite_integral(a, b)
+    }
+
+    fn roots_in(&self, a: f32, b: f32) -> Vec<Root> {
+        self.real_roots()
+            .into_iter()
+            .filter(|r| r.x >= a && r.x <= b)
+            .collect()
+    }
+
+    fn solve(&self, y: f32, a: f32, b: f32) -> Vec<f32> {
+        (self - &Polynomial::constant(y))
+            .roots_in(a, b)
+            .into_iter()
+            .map(|root| root.x)
+            .collect()
+    }
+
+    // the curves meet at the roots of their difference
+    fn intersections_with(&self, other: &Self, a: f32, b: f32) -> Vec<Point> {
+        (self - other)
+            .roots_in(a, b)
+            .into_iter()
+            .map(|root| Point {
+                x: root.x,
+                y: self.calc(root.x),
+            })
+            .collect()
+    }
 }
 
 impl Display for Polynomial {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "y = ").expect("failed to write");
+        if self.terms.is_empty() {
+            return write!(f, "0");
+        }
         for item in self.terms.iter() {
             // Print each element
             write!(f, "{} ", item)?;
@@ -104,6 +470,80 @@ impl Display for Polynomial {
     }
 }
 
+impl Neg for &Polynomial {
+    type Output = Polynomial;
+    fn neg(self) -> Self::Output {
+        Polynomial::combined(self.terms.iter().map(|t| Term::new(-t.c, t.power)))
+    }
+}
+
+impl Add for &Polynomial {
+    type Output = Polynomial;
+    fn add(self, rhs: Self) -> Self::Output {
+        Polynomial::combined(self.terms.iter().chain(&rhs.terms).copied())
+    }
+}
+
+impl Sub for &Polynomial {
+    type Output = Polynomial;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + &-rhs
+    }
+}
+
+impl Mul for &Polynomial {
+    type Output = Polynomial;
+    fn mul(self, rhs: Self) -> Self::Output {
+        let products = self.terms.iter().flat_map(|a| {
+            rhs.terms.iter().map(move |b| {
+                // the parser caps degrees well below this
+                let power = a.power.checked_add(b.power).expect("power overflowed");
+                Term::new(a.c * b.c, power)
+            })
+        });
+        Polynomial::combined(products)
+    }
+}
+
+impl Div for &Polynomial {
+    type Output = Polynomial;
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem for &Polynomial {
+    type Output = Polynomial;
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.div_rem(rhs).1
+    }
+}
+
+// owned operands just borrow, so both p + q and &p + &q work
+macro_rules! forward_owned_op {
+    ($op:ident, $method:ident) => {
+        impl $op for Polynomial {
+            type Output = Polynomial;
+            fn $method(self, rhs: Self) -> Self::Output {
+                (&self).$method(&rhs)
+            }
+        }
+    };
+}
+
+forward_owned_op!(Add, add);
+forward_owned_op!(Sub, sub);
+forward_owned_op!(Mul, mul);
+forward_owned_op!(Div, div);
+forward_owned_op!(Rem, rem);
+
+impl Neg for Polynomial {
+    type Output = Polynomial;
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
 pub trait CouldBeLinear {
     fn is_linear(&self) -> bool;
 }
@@ -136,6 +576,183 @@ impl CouldBeQuad for Polynomial {
     }
 }
 
+pub trait CouldBeCubic {
+    fn is_cubic(&self) -> bool;
+}
+
+impl CouldBeCubic for Polynomial {
+    fn is_cubic(&self) -> bool {
+        self.terms.iter().all(|t| t.power <= 3)
+    }
+}
+
+// anything that can be graphed, so polynomials and general expressions can be mixed
+#[derive(Debug)]
+pub enum Equation {
+    Polynomial(Polynomial),
+    Expression(Expression),
+}
+
+impl Equation {
+    // points worth having on screen: the y intercept, roots, and extrema or inflection points
+    // all of them for polynomials, other equations can have endlessly many so only those in [a, b]
+    pub fn features(&self, a: f32, b: f32) -> Vec<Point> {
+        let mut points = vec![Point {
+            x: 0.,
+            y: self.calc(0.),
+        }];
+
+        match self {
+            Equation::Polynomial(p) => {
+                points.extend(p.real_roots().iter().map(|root| Point { x: root.x, y: 0. }));
+                points.extend(p.critical_points().iter().map(|critical| critical.point));
+            }
+            Equation::Expression(e) => {
+                points.extend(
+                    e.roots_in(a, b)
+                        .iter()
+                        .map(|root| Point { x: root.x, y: 0. }),
+                );
+                // turning points are where the slope crosses 0
+                let samples = 1000;
+                let slope = |x: f64| e.slope(x as f32) as f64;
+                points.extend(
+                    analysis::sampled_roots(slope, a as f64, b as f64, samples)
+                        .into_iter()
+                        .map(|x| Point {
+                            x: x as f32,
+                            y: e.calc(x as f32),
+                        }),
+                );
+            }
+        }
+
+        points.retain(|point| point.x.is_finite() && point.y.is_finite());
+        points
+    }
+}
+
+impl Calculate for Equation {
+    fn calc(&self, x: f32) -> f32 {
+        match self {
+            Equation::Polynomial(p) => p.calc(x),
+            Equation::Expression(e) => e.calc(x),
+        }
+    }
+
+    fn slope(&self, x: f32) -> f32 {
+        match self {
+            Equation::Polynomial(p) => p.slope(x),
+            Equation::Expression(e) => e.slope(x),
+        }
+    }
+
+    fn cubic_error_bound(&self, a: f32, b: f32) -> Option<f32> {
+        match self {
+            Equation::Polynomial(p) => p.cubic_error_bound(a, b),
+            Equation::Expression(e) => e.cubic_error_bound(a, b),
+        }
+    }
+
+    fn integrate(&self, a: f32, b: f32) -> f32 {
+        match self {
+            Equation::Polynomial(p) => p.integrate(a, b),
+            Equation::Expression(e) => e.integrate(a, b),
+        }
+    }
+
+    fn roots_in(&self, a: f32, b: f32) -> Vec<Root> {
+        match self {
+            Equation::Polynomial(p) => p.roots_in(a, b),
+            Equation::Expression(e) => e.roots_in(a, b),
+        }
+    }
+
+    fn solve(&self, y: f32, a: f32, b: f32) -> Vec<f32> {
+        match self {
+            Equation::Polynomial(p) => p.solve(y, a, b),
+            Equation::Expression(e) => e.solve(y, a, b),
+        }
+    }
+
+    fn intersections_with(&self, other: &Self, a: f32, b: f32) -> Vec<Point> {
+        match (self, other) {
+            (Equation::Polynomial(p), Equation::Polynomial(q)) => p.intersections_with(q, a, b),
+            _ => numeric_intersections(self, other, a, b),
+        }
+    }
+}
+
+impl Display for Equation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Equation::Polynomial(p) => write!(f, "{}", p),
+            Equation::Expression(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl CouldBeLinear for Equation {
+    fn is_linear(&self) -> bool {
+        match self {
+            Equation::Polynomial(p) => p.is_linear(),
+            Equation::Expression(e) => e.is_linear(),
+        }
+    }
+}
+
+impl CouldBeQuad for Equation {
+    fn is_quadratic(&self) -> bool {
+        match self {
+            Equation::Polynomial(p) => p.is_quadratic(),
+            Equation::Expression(e) => e.is_quadratic(),
+        }
+    }
+}
+
+impl CouldBeCubic for Equation {
+    fn is_cubic(&self) -> bool {
+        match self {
+            Equation::Polynomial(p) => p.is_cubic(),
+            Equation::Expression(e) => e.is_cubic(),
+        }
+    }
+}
+
+impl From<Polynomial> for Equation {
+    fn from(p: Polynomial) -> Self {
+        Equation::Polynomial(p)
+    }
+}
+
+impl From<Expression> for Equation {
+    fn from(e: Expression) -> Self {
+        Equation::Expression(e)
+    }
+}
+
+// the same curve as an expression tree, so it can be combined with non-polynomials
+impl From<Polynomial> for Expression {
+    fn from(p: Polynomial) -> Self {
+        let terms = p.terms.iter().map(|t| {
+            let x_power = match t.power {
+                0 => return Expression::constant(t.c),
+                1 => Expression::x(),
+                power => Expression::x().pow(Expression::constant(power as f32)),
+            };
+            if t.c == 1. {
+                x_power
+            } else {
+                Expression::constant(t.c) * x_power
+            }
+        });
+
+        terms
+            .reduce(|sum, term| sum + term)
+            .unwrap_or(Expression::constant(0.))
+    }
+}
+
 pub struct PolynomialBuilder {
     terms: Vec<Term>,
 }
@@ -170,7 +787,7 @@ impl PolynomialBuilder {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Term {
     power: i32,
     c: f32,
@@ -198,3 +815,217 @@ impl Display for Term {
         write!(f, "{} {}{}", sign, self.c.abs(), x_power)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grapher::expression::Function;
+
+    #[test]
+    fn derivative_uses_power_rule() {
+        // 0.5x^6 - 4x^4 + 3x^2 - 1
+        let poly = PolynomialBuilder::new()
+            .add_term(Term::x_to_the(6).times(0.5))
+            .plus_x_4th_times(-4.)
+            .plus_x_squared_times(3.)
+            .plus_const(-1.)
+            .build();
+        let expected =
+            Polynomial::new(vec![Term::new(3., 5), Term::new(-16., 3), Term::new(6., 1)]);
+
+        assert_eq!(poly.derivative(), expected);
+
+        // small coefficients aren't rounded away
+        let small = Polynomial::new(vec![Term::new(0.00002, 3), Term::new(-3., 1)]);
+        let expected = Polynomial::new(vec![Term::new(0.00006, 2), Term::new(-3., 0)]);
+        assert_eq!(small.derivative(), expected);
+        assert_eq!(
+            small.nth_derivative(2),
+            Polynomial::new(vec![Term::new(0.00012, 1)])
+        );
+    }
+
+    #[test]
+    fn nth_derivative() {
+        let cubic = PolynomialBuilder::new()
+            .plus_x_cubed_times(2.)
+            .plus_x_times(1.)
+            .build();
+
+        assert_eq!(cubic.nth_derivative(0), cubic);
+        assert_eq!(
+            cubic.nth_derivative(2),
+            Polynomial::new(vec![Term::new(12., 1)])
+        );
+        assert_eq!(cubic.nth_derivative(4), Polynomial::new(vec![]));
+    }
+
+    #[test]
+    fn integral_inverts_derivative() {
+        // 3x^2 - 2x + 1
+        let poly = PolynomialBuilder::new()
+            .plus_x_squared_times(3.)
+            .plus_x_times(-2.)
+            .plus_const(1.)
+            .build();
+        let expected = Polynomial::new(vec![Term::new(1., 3), Term::new(-1., 2), Term::new(1., 1)]);
+
+        assert_eq!(poly.integral(), expected);
+        assert_eq!(poly.integral().derivative(), poly);
+        assert_eq!(poly.definite_integral(0., 2.), 6.);
+        assert_eq!(poly.definite_integral(2., 0.), -6.);
+    }
+
+    #[test]
+    fn numeric_integration_of_expressions() {
+        // the area under one hump of sin(x) is 2
+        let sin = Expression::call(Function::Sin, Expression::x());
+
+        assert!((sin.integrate(0., std::f32::consts::PI) - 2.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn real_roots_with_multiplicity() {
+        // 0.01x^3 - 0.2x^2 + x = 0.01x(x - 10)^2
+        let cubic = PolynomialBuilder::new()
+            .plus_x_cubed_times(0.01)
+            .plus_x_squared_times(-0.2)
+            .plus_x_times(1.)
+            .build();
+
+        let roots = cubic.real_roots();
+
+        assert_eq!(roots.len(), 2);
+        assert_eq!(roots[0].x, 0.);
+        assert_eq!(roots[0].multiplicity, 1);
+        assert!((roots[1].x - 10.).abs() < 1e-3);
+        assert_eq!(roots[1].multiplicity, 2);
+        assert_eq!(cubic.roots_in(-1., 5.), vec![roots[0]]);
+    }
+
+    #[test]
+    fn critical_points_of_quartic() {
+        // x^4 - 2x^2 has minima at -1 and 1, a maximum at 0, and inflections at +-1/sqrt(3)
+        let quartic = PolynomialBuilder::new()
+            .plus_x_4th_times(1.)
+            .plus_x_squared_times(-2.)
+            .build();
+
+        let points = quartic.critical_points();
+        let kinds: Vec<CriticalPointKind> = points.iter().map(|p| p.kind).collect();
+        let inflection_x = 1. / 3_f32.sqrt();
+
+        assert_eq!(
+            kinds,
+            vec![
+                CriticalPointKind::LocalMinimum,
+                CriticalPointKind::Inflection,
+                CriticalPointKind::LocalMaximum,
+                CriticalPointKind::Inflection,
+                CriticalPointKind::LocalMinimum,
+            ]
+        );
+        assert!((points[0].point.x + 1.).abs() < 1e-5);
+        assert!((points[0].point.y + 1.).abs() < 1e-5);
+        assert!((points[1].point.x + inflection_x).abs() < 1e-5);
+        assert!(points[2].point.x.abs() < 1e-5);
+    }
+
+    #[test]
+    fn flat_inflection_is_not_an_extremum() {
+        let cubic = PolynomialBuilder::new().plus_x_cubed_times(1.).build();
+
+        assert!(cubic.extrema().is_empty());
+        assert_eq!(
+            cubic.inflection_points(),
+            vec![CriticalPoint {
+                kind: CriticalPointKind::Inflection,
+                point: Point { x: 0., y: 0. },
+            }]
+        );
+    }
+
+    #[test]
+    fn intersections_of_polynomials_and_expressions() {
+        // x^2 - 1 meets x + 1 at x = -1 and x = 2
+        let quad: Equation = PolynomialBuilder::new()
+            .plus_x_squared_times(1.)
+            .plus_const(-1.)
+            .build()
+            .into();
+        let line: Equation = PolynomialBuilder::new()
+            .plus_x_times(1.)
+            .plus_const(1.)
+            .build()
+            .into();
+        let exp: Equation = Expression::call(Function::Exp, Expression::x()).into();
+
+        assert_eq!(
+            quad.intersections_with(&line, -10., 10.),
+            vec![Point { x: -1., y: 0. }, Point { x: 2., y: 3. }]
+        );
+
+        let crossings = exp.intersections_with(&quad, -10., 10.);
+        assert_eq!(crossings.len(), 1);
+        assert!((exp.calc(crossings[0].x) - quad.calc(crossings[0].x)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn polynomial_arithmetic() {
+        let p = Polynomial::x() - Polynomial::constant(1.);
+        let q = Polynomial::x() + Polynomial::constant(2.);
+
+        assert_eq!(
+            &p * &q,
+            Polynomial::new(vec![Term::new(1., 2), Term::new(1., 1), Term::new(-2., 0)])
+        );
+        assert_eq!(&p - &p, Polynomial::new(vec![]));
+        assert_eq!(-&p + p.clone(), Polynomial::new(vec![]));
+        assert_eq!(p.pow(3).calc(3.), 8.);
+        // p(q(x)) = (x + 2) - 1
+        assert_eq!(
+            p.compose(&q),
+            Polynomial::new(vec![Term::new(1., 1), Term::new(1., 0)])
+        );
+    }
+
+    #[test]
+    fn long_division_and_gcd() {
+        // x^3 - 2x^2 - 5x + 6 = (x - 1)(x + 2)(x - 3)
+        let cubic = PolynomialBuilder::new()
+            .plus_x_cubed_times(1.)
+            .plus_x_squared_times(-2.)
+            .plus_x_times(-5.)
+            .plus_const(6.)
+            .build();
+        let factor = Polynomial::x() - Polynomial::constant(1.);
+
+        let (quotient, remainder) = cubic.div_rem(&factor);
+        assert_eq!(
+            quotient,
+            Polynomial::new(vec![Term::new(1., 2), Term::new(-1., 1), Term::new(-6., 0)])
+        );
+        assert!(remainder.is_zero());
+        assert_eq!(&cubic % &Polynomial::x(), Polynomial::constant(6.));
+
+        // 2x^2 - 2 = 2(x - 1)(x + 1)
+        let other = Polynomial::constant(2.) * Polynomial::x().pow(2) - Polynomial::constant(2.);
+        assert_eq!(cubic.gcd(&other), factor);
+    }
+    #[test]
+    fn slopes_and_cubic_error_bounds() {
+        // x^4 - x^3, slope 4x^3 - 3x^2
+        let quartic = PolynomialBuilder::new()
+            .plus_x_4th_times(1.)
+            .plus_x_cubed_times(-1.)
+            .build();
+        let cubic = PolynomialBuilder::new().plus_x_cubed_times(-1.).build();
+
+        assert_eq!(quartic.slope(2.), 20.);
+        assert_eq!(cubic.cubic_error_bound(-10., 10.), Some(0.));
+        // the fourth derivative is 24, so the bound is 24 * 2^4 / 384
+        assert_eq!(quartic.cubic_error_bound(0., 2.), Some(1.));
+        assert_eq!(Expression::x().cubic_error_bound(0., 2.), None);
+        assert!((Expression::x().pow(Expression::constant(2.)).slope(3.) - 6.).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,330 @@
+use std::fmt::Display;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use super::equation::{Calculate, CouldBeCubic, CouldBeLinear, CouldBeQuad};
+
+// elementary functions that can be applied to an expression
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Exp,
+    Ln,
+    Log10,
+    Sqrt,
+    Abs,
+}
+
+impl Function {
+    pub const ALL: [Function; 14] = [
+        Function::Sin,
+        Function::Cos,
+        Function::Tan,
+        Function::Asin,
+        Function::Acos,
+        Function::Atan,
+        Function::Sinh,
+        Function::Cosh,
+        Function::Tanh,
+        Function::Exp,
+        Function::Ln,
+        Function::Log10,
+        Function::Sqrt,
+        Function::Abs,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Function::Sin => "sin",
+            Function::Cos => "cos",
+            Function::Tan => "tan",
+            Function::Asin => "asin",
+            Function::Acos => "acos",
+            Function::Atan => "atan",
+            Function::Sinh => "sinh",
+            Function::Cosh => "cosh",
+            Function::Tanh => "tanh",
+            Function::Exp => "exp",
+            Function::Ln => "ln",
+            Function::Log10 => "log",
+            Function::Sqrt => "sqrt",
+            Function::Abs => "abs",
+        }
+    }
+
+    pub fn apply(&self, x: f32) -> f32 {
+        match self {
+            Function::Sin => x.sin(),
+            Function::Cos => x.cos(),
+            Function::Tan => x.tan(),
+            Function::Asin => x.asin(),
+            Function::Acos => x.acos(),
+            Function::Atan => x.atan(),
+            Function::Sinh => x.sinh(),
+            Function::Cosh => x.cosh(),
+            Function::Tanh => x.tanh(),
+            Function::Exp => x.exp(),
+            Function::Ln => x.ln(),
+            Function::Log10 => x.log10(),
+            Function::Sqrt => x.sqrt(),
+            Function::Abs => x.abs(),
+        }
+    }
+}
+
+// expression tree in terms of x
+// anything that isn't a polynomial (sin(x), e^x, |x|, ...) is graphed through this
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Const(f32),
+    X,
+    Neg(Box<Expression>),
+    Add(Box<Expression>, Box<Expression>),
+    Sub(Box<Expression>, Box<Expression>),
+    Mul(Box<Expression>, Box<Expression>),
+    Div(Box<Expression>, Box<Expression>),
+    Pow(Box<Expression>, Box<Expression>),
+    Func(Function, Box<Expression>),
+}
+
+impl Expression {
+    pub fn x() -> Self {
+        Expression::X
+    }
+    pub fn constant(c: f32) -> Self {
+        Expression::Const(c)
+    }
+    pub fn call(function: Function, arg: Expression) -> Self {
+        Expression::Func(function, Box::new(arg))
+    }
+    pub fn pow(self, exponent: Expression) -> Self {
+        Expression::Pow(Box::new(self), Box::new(exponent))
+    }
+
+    // degree if the expression is a polynomial in x, None otherwise
+    // an upper bound: x^2 - x^2 still reports 2
+    pub fn polynomial_degree(&self) -> Option<i32> {
+        match self {
+            Expression::Const(_) => Some(0),
+            Expression::X => Some(1),
+            Expression::Neg(a) => a.polynomial_degree(),
+            Expression::Add(a, b) | Expression::Sub(a, b) => {
+                Some(a.polynomial_degree()?.max(b.polynomial_degree()?))
+            }
+            Expression::Mul(a, b) => Some(a.polynomial_degree()? + b.polynomial_degree()?),
+            Expression::Div(a, b) => match b.polynomial_degree()? {
+                0 => a.polynomial_degree(),
+                _ => None,
+            },
+            Expression::Pow(base, exponent) => {
+                let base_degree = base.polynomial_degree()?;
+                if exponent.polynomial_degree()? != 0 {
+                    return None;
+                }
+                if base_degree == 0 {
+                    return Some(0);
+                }
+
+                let n = exponent.calc(0.);
+                if n >= 0. && n.fract() == 0. {
+                    Some(base_degree * n as i32)
+                } else {
+                    None
+                }
+            }
+            Expression::Func(_, arg) => match arg.polynomial_degree()? {
+                0 => Some(0),
+                _ => None,
+            },
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Expression::Add(..) | Expression::Sub(..) => 1,
+            Expression::Mul(..) | Expression::Div(..) => 2,
+            Expression::Neg(_) => 3,
+            Expression::Const(c) if c.is_sign_negative() => 3,
+            Expression::Pow(..) => 4,
+            Expression::Const(_) | Expression::X | Expression::Func(..) => 5,
+        }
+    }
+
+    // writes the expression without the "y = ", adding parentheses
+    // only where the parent binds tighter than this node
+    fn write_body(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        parent_precedence: u8,
+    ) -> std::fmt::Result {
+        let needs_parens = self.precedence() < parent_precedence;
+        if needs_parens {
+            write!(f, "(")?;
+        }
+
+        let p = self.precedence();
+        match self {
+            Expression::Const(c) => write!(f, "{}", c)?,
+            Expression::X => write!(f, "x")?,
+            Expression::Neg(a) => {
+                write!(f, "-")?;
+                a.write_body(f, p + 1)?;
+            }
+            Expression::Add(a, b) => {
+                a.write_body(f, p)?;
+                write!(f, " + ")?;
+                b.write_body(f, p)?;
+            }
+            Expression::Sub(a, b) => {
+                a.write_body(f, p)?;
+                write!(f, " - ")?;
+                b.write_body(f, p + 1)?;
+            }
+            Expression::Mul(a, b) => {
+                a.write_body(f, p)?;
+                write!(f, " * ")?;
+                b.write_body(f, p)?;
+            }
+            Expression::Div(a, b) => {
+                a.write_body(f, p)?;
+                write!(f, " / ")?;
+                b.write_body(f, p + 1)?;
+            }
+            Expression::Pow(base, exponent) => {
+                base.write_body(f, p + 1)?;
+                write!(f, "^")?;
+                exponent.write_body(f, p)?;
+            }
+            Expression::Func(function, arg) => {
+                write!(f, "{}(", function.name())?;
+                arg.write_body(f, 0)?;
+                write!(f, ")")?;
+            }
+        }
+
+        if needs_parens {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl Calculate for Expression {
+    fn calc(&self, x: f32) -> f32 {
+        match self {
+            Expression::Const(c) => *c,
+            Expression::X => x,
+            Expression::Neg(a) => -a.calc(x),
+            Expression::Add(a, b) => a.calc(x) + b.calc(x),
+            Expression::Sub(a, b) => a.calc(x) - b.calc(x),
+            Expression::Mul(a, b) => a.calc(x) * b.calc(x),
+            Expression::Div(a, b) => a.calc(x) / b.calc(x),
+            Expression::Pow(base, exponent) => base.calc(x).powf(exponent.calc(x)),
+            Expression::Func(function, arg) => function.apply(arg.calc(x)),
+        }
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "y = ")?;
+        self.write_body(f, 0)
+    }
+}
+
+impl CouldBeLinear for Expression {
+    fn is_linear(&self) -> bool {
+        matches!(self.polynomial_degree(), Some(d) if d <= 1)
+    }
+}
+
+impl CouldBeQuad for Expression {
+    fn is_quadratic(&self) -> bool {
+        matches!(self.polynomial_degree(), Some(d) if d <= 2)
+    }
+}
+
+impl CouldBeCubic for Expression {
+    fn is_cubic(&self) -> bool {
+        matches!(self.polynomial_degree(), Some(d) if d <= 3)
+    }
+}
+
+impl Neg for Expression {
+    type Output = Expression;
+    fn neg(self) -> Self::Output {
+        Expression::Neg(Box::new(self))
+    }
+}
+
+impl Add for Expression {
+    type Output = Expression;
+    fn add(self, rhs: Self) -> Self::Output {
+        Expression::Add(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl Sub for Expression {
+    type Output = Expression;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Expression::Sub(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl Mul for Expression {
+    type Output = Expression;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Expression::Mul(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl Div for Expression {
+    type Output = Expression;
+    fn div(self, rhs: Self) -> Self::Output {
+        Expression::Div(Box::new(self), Box::new(rhs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calculates_functions() {
+        // 2sin(x) + e^x
+        let expr = Expression::constant(2.) * Expression::call(Function::Sin, Expression::x())
+            + Expression::call(Function::Exp, Expression::x());
+
+        let x = 0.5_f32;
+        assert!((expr.calc(x) - (2. * x.sin() + x.exp())).abs() < 1e-6);
+    }
+
+    #[test]
+    fn classifies_polynomial_expressions() {
+        let linear = Expression::constant(3.) * Expression::x() - Expression::constant(1.);
+        let quad = (Expression::x() + Expression::constant(1.)).pow(Expression::constant(2.));
+        let not_poly = Expression::call(Function::Sqrt, Expression::x());
+        let const_func = Expression::call(Function::Ln, Expression::constant(2.)) * Expression::x();
+
+        assert!(linear.is_linear());
+        assert!(!quad.is_linear() && quad.is_quadratic());
+        assert!(!not_poly.is_quadratic());
+        assert!(const_func.is_linear());
+    }
+
+    #[test]
+    fn displays_with_minimal_parentheses() {
+        let expr = (Expression::x() - Expression::constant(1.)).pow(Expression::constant(2.))
+            / (Expression::x() + Expression::constant(2.))
+            - Expression::call(Function::Abs, -Expression::x());
+
+        assert_eq!(expr.to_string(), "y = (x - 1)^2 / (x + 2) - abs(-x)");
+    }
+}
@@ -1,252 +1,1157 @@
-use femtovg::{renderer::OpenGl, Canvas, Color, Paint, Path};
-use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::dpi::{LogicalPosition, LogicalSize};
 
-use super::equation::{Calculate, CouldBeLinear, CouldBeQuad, Point};
+use super::drawing::{Align, Baseline, Color, Paint, Path, Surface};
+use super::equation::{
+    Calculate, CouldBeCubic, CouldBeLinear, CouldBeQuad, CriticalPoint, CriticalPointKind, Point,
+};
+use super::sampling;
+use super::style::{self, Style};
+
+// evaluations shared by all the curves in a frame, so very high degree equations can't stall it
+const SAMPLE_BUDGET: usize = 50_000;
+// curves are drawn this far past the top and bottom of the screen so line ends stay out of sight
+const CULL_MARGIN_PX: f32 = 50.;
+
+// pixels per unit the graph starts at and goes back to on reset
+pub const DEFAULT_SCALE: f32 = 50.;
+
+// pixels per unit along each axis, or per power of ten on a log axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scale {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Scale {
+    pub fn uniform(scale: f32) -> Self {
+        Scale { x: scale, y: scale }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum AxisMode {
+    #[default]
+    Linear,
+    Log,
+}
+
+impl AxisMode {
+    // where a value sits along the axis before scaling, its log10 on a log axis
+    // 0 and negative values have no place on a log axis and come out as NaN, so they aren't drawn
+    fn position(self, value: f32) -> f32 {
+        match self {
+            AxisMode::Linear => value,
+            AxisMode::Log if value > 0. => value.log10(),
+            AxisMode::Log => f32::NAN,
+        }
+    }
+
+    // inverse of position
+    fn value(self, position: f32) -> f32 {
+        match self {
+            AxisMode::Linear => position,
+            AxisMode::Log => 10_f32.powf(position),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct AxisModes {
+    pub x: AxisMode,
+    pub y: AxisMode,
+}
+
+impl AxisModes {
+    // linear, semi-log in y, semi-log in x, log-log, then back to linear
+    pub fn next(self) -> Self {
+        use AxisMode::{Linear, Log};
+        let (x, y) = match (self.x, self.y) {
+            (Linear, Linear) => (Linear, Log),
+            (Linear, Log) => (Log, Linear),
+            (Log, Linear) => (Log, Log),
+            (Log, Log) => (Linear, Linear),
+        };
+        AxisModes { x, y }
+    }
+
+    fn any_log(&self) -> bool {
+        self.x == AxisMode::Log || self.y == AxisMode::Log
+    }
+}
 
 // graph should be responsible for all paths and pixel conversions
+// it draws on any surface, eg: the window's canvas or a recording for tests
 // so that other structs can mathematical units
-pub struct Graph<'a> {
-    pub size: PhysicalSize<u32>,
-    pub scale: f32,
-    offset: PhysicalPosition<f32>,
-    pub canvas: &'a mut Canvas<OpenGl>,
+// pixels are logical, so the graph is the same size on high dpi screens
+pub struct Graph<'a, S: Surface> {
+    pub size: LogicalSize<f32>,
+    pub scale: Scale,
+    axes: AxisModes,
+    offset: LogicalPosition<f32>,
+    pub surface: &'a mut S,
+    samples_left: usize,
 }
 
-impl<'a> Graph<'a> {
+impl<'a, S: Surface> Graph<'a, S> {
     pub fn new(
-        size: PhysicalSize<u32>,
-        scale: f32,
-        offset: PhysicalPosition<f32>,
-        canvas: &'a mut Canvas<OpenGl>,
+        size: LogicalSize<f32>,
+        scale: Scale,
+        offset: LogicalPosition<f32>,
+        surface: &'a mut S,
     ) -> Self {
         Graph {
             size,
             scale,
-            canvas,
+            axes: AxisModes::default(),
+            surface,
             offset,
+            samples_left: SAMPLE_BUDGET,
         }
     }
 
+    pub fn with_axes(mut self, axes: AxisModes) -> Self {
+        self.axes = axes;
+        self
+    }
+
     pub fn init_graph(&mut self) {
         self.draw_axes();
         self.draw_tick_marks();
+        self.draw_tick_labels();
     }
 
+    // a log axis has no 0, so the other axis isn't drawn
     fn draw_axes(&mut self) {
         let mut axes = Path::new();
+        let (zero_x, zero_y) = self.zero_zero_px();
+
+        if self.axes.y == AxisMode::Linear {
+            axes.move_to(0., zero_y);
+            axes.line_to(self.size.width, zero_y);
+        }
+        if self.axes.x == AxisMode::Linear {
+            axes.move_to(zero_x, 0.);
+            axes.line_to(zero_x, self.size.height);
+        }
+
+        let axes_paint = Paint::color(Color::rgb(0, 255, 0)).with_line_width(3.);
+        self.surface.stroke_path(&axes, &axes_paint);
+    }
 
+    // gridlines every minor step, with the major ones wider
+    fn draw_tick_marks(&mut self) {
         let (min_x, max_x) = self.get_x_range();
         let (min_y, max_y) = self.get_y_range();
 
-        let x_axis_start = self.convert_point_to_px(Point::from_ints(min_x, 0));
-        let x_axis_end = self.convert_point_to_px(Point::from_ints(max_x, 0));
-
-        let y_axis_start = self.convert_point_to_px(Point::from_ints(0, min_y));
-        let y_axis_end = self.convert_point_to_px(Point::from_ints(0, max_y));
+        let mut ticks_path = Path::new();
+        let mut significant_ticks_path = Path::new();
 
-        axes.move_to(x_axis_start.0, x_axis_start.1);
-        axes.line_to(x_axis_end.0, x_axis_start.1);
+        for (x, major) in gridlines(self.axes.x, self.scale.x, min_x, max_x) {
+            let x_px = self.x_to_px(x);
+            let path = if major {
+                &mut significant_ticks_path
+            } else {
+                &mut ticks_path
+            };
+            path.move_to(x_px, 0.);
+            path.line_to(x_px, self.size.height);
+        }
 
-        axes.move_to(y_axis_start.0, y_axis_start.1);
-        axes.line_to(y_axis_end.0, y_axis_end.1);
+        for (y, major) in gridlines(self.axes.y, self.scale.y, min_y, max_y) {
+            let y_px = self.y_to_px(y);
+            let path = if major {
+                &mut significant_ticks_path
+            } else {
+                &mut ticks_path
+            };
+            path.move_to(0., y_px);
+            path.line_to(self.size.width, y_px);
+        }
 
-        let axes_paint = Paint::color(Color::rgb(0, 255, 0)).with_line_width(3.);
-        self.canvas.stroke_path(&axes, &axes_paint);
+        let green_paint = Paint::color(Color::rgb(0, 255, 0)).with_line_width(0.4);
+        let wider_paint = green_paint.clone().with_line_width(0.6);
+        self.surface.stroke_path(&ticks_path, &green_paint);
+        self.surface
+            .stroke_path(&significant_ticks_path, &wider_paint);
     }
 
-    fn draw_tick_marks(&mut self) {
+    // numbers along both axes at every major gridline
+    // an axis that's off screen has its labels pinned to the nearest edge instead,
+    // and without an axis to follow, eg: x labels when y is log, they sit on the bottom or left edge
+    fn draw_tick_labels(&mut self) {
+        let padding = 4.;
+        let font_size = 12.;
+        let width = self.size.width;
+        let height = self.size.height;
+
         let (min_x, max_x) = self.get_x_range();
         let (min_y, max_y) = self.get_y_range();
+        let (zero_x, zero_y) = self.zero_zero_px();
+        let axis_x = match self.axes.x {
+            AxisMode::Linear => zero_x,
+            AxisMode::Log => 0.,
+        };
+        let axis_y = match self.axes.y {
+            AxisMode::Linear => zero_y,
+            AxisMode::Log => height,
+        };
 
-        let mut ticks_path = Path::new();
-        let mut significant_ticks_path = Path::new();
+        let paint = Paint::color(Color::rgb(0, 255, 0)).with_font_size(font_size);
 
-        for x in min_x..(max_x + 1) {
-            let start_px = self.convert_point_to_px(Point::from_ints(x, min_y));
-            let end_px = self.convert_point_to_px(Point::from_ints(x, max_y));
+        // under the x axis, or along the top or bottom edge
+        let label_y = (axis_y + padding).clamp(padding, height - font_size - padding);
+        let x_paint = paint
+            .clone()
+            .with_text_align(Align::Center)
+            .with_text_baseline(Baseline::Top);
+        for (x, major) in gridlines(self.axes.x, self.scale.x, min_x, max_x) {
+            if !major {
+                continue;
+            }
+            let label = tick_label(self.axes.x, self.scale.x, x);
 
-            if x % 10 == 0 {
-                significant_ticks_path.move_to(start_px.0, start_px.1);
-                significant_ticks_path.line_to(end_px.0, end_px.1);
-            } else if self.scale > 5. {
-                ticks_path.move_to(start_px.0, start_px.1);
-                ticks_path.line_to(end_px.0, end_px.1);
+            // a label cut off at the edge reads as a different number, eg: the 0 of -10
+            let half_width = self.surface.measure_text(&label, &x_paint) / 2.;
+            let x_px = self.x_to_px(x);
+            if x_px - half_width < 0. || x_px + half_width > width {
+                continue;
             }
+            self.surface.fill_text(x_px, label_y, &label, &x_paint);
         }
 
-        for y in min_y..(max_y + 1) {
-            let start_px = self.convert_point_to_px(Point::from_ints(min_x, y));
-            let end_px = self.convert_point_to_px(Point::from_ints(max_x, y));
+        // right of the y axis, or along the left or right edge
+        let y_paint = paint.with_text_baseline(Baseline::Middle);
+        for (y, major) in gridlines(self.axes.y, self.scale.y, min_y, max_y) {
+            // 0 is already labelled on the x axis
+            if !major || y == 0. {
+                continue;
+            }
+            let label = tick_label(self.axes.y, self.scale.y, y);
 
-            if y % 10 == 0 {
-                significant_ticks_path.move_to(start_px.0, start_px.1);
-                significant_ticks_path.line_to(end_px.0, end_px.1);
-            } else if self.scale > 5. {
-                ticks_path.move_to(start_px.0, start_px.1);
-                ticks_path.line_to(end_px.0, end_px.1);
+            let y_px = self.y_to_px(y);
+            if y_px - font_size / 2. < 0. || y_px + font_size / 2. > height {
+                continue;
             }
-        }
 
-        let green_paint = Paint::color(Color::rgb(0, 255, 0)).with_line_width(0.4);
-        let wider_paint = green_paint.clone().with_line_width(0.6);
-        self.canvas.stroke_path(&ticks_path, &green_paint);
-        self.canvas
-            .stroke_path(&significant_ticks_path, &wider_paint);
+            let label_width = self.surface.measure_text(&label, &y_paint);
+            let label_x = (axis_x + padding).clamp(padding, width - label_width - padding);
+            self.surface.fill_text(label_x, y_px, &label, &y_paint);
+        }
     }
 
     fn zero_zero_px(&self) -> (f32, f32) {
-        let zero_x_px: f32 = (self.size.width / 2) as f32;
-        let zero_y_px: f32 = (self.size.height / 2) as f32;
+        let zero_x_px = self.size.width / 2.;
+        let zero_y_px = self.size.height / 2.;
 
         (zero_x_px + self.offset.x, zero_y_px + self.offset.y)
     }
 
-    // get x range in units, returns the first int greater than the screen size
-    fn get_x_range(&self) -> (i32, i32) {
-        let (zero_x, _zero_y) = self.zero_zero_px();
+    // the x values at the left and right edges of the screen
+    pub fn get_x_range(&self) -> (f32, f32) {
+        let left = self.convert_px_to_point((0., 0.));
+        let right = self.convert_px_to_point((self.size.width, 0.));
 
-        let num_x_ticks_left = (zero_x / self.scale).ceil() as i32; // without ceil the edge would be empty until more than half is showing
-        let num_x_ticks_right = ((self.size.width as f32 - zero_x) / self.scale).ceil() as i32;
+        (left.x, right.x)
+    }
+    // the y values at the bottom and top edges of the screen
+    fn get_y_range(&self) -> (f32, f32) {
+        let bottom = self.convert_px_to_point((0., self.size.height));
+        let top = self.convert_px_to_point((0., 0.));
 
-        let min_x = num_x_ticks_left * -1;
-        let max_x = num_x_ticks_right;
+        (bottom.y, top.y)
+    }
 
-        (min_x, max_x)
+    fn x_to_px(&self, x: f32) -> f32 {
+        self.zero_zero_px().0 + self.axes.x.position(x) * self.scale.x
+    }
+    fn y_to_px(&self, y: f32) -> f32 {
+        self.zero_zero_px().1 - self.axes.y.position(y) * self.scale.y
     }
-    fn get_y_range(&self) -> (i32, i32) {
-        let (_zero_x, zero_y) = self.zero_zero_px();
 
-        let num_y_ticks_above = (zero_y / self.scale).ceil() as i32;
-        let num_y_ticks_below = ((self.size.height as f32 - zero_y) / self.scale).ceil() as i32;
+    fn convert_point_to_px(&self, point: Point) -> (f32, f32) {
+        (self.x_to_px(point.x), self.y_to_px(point.y))
+    }
 
-        let min_y = num_y_ticks_below * -1;
-        let max_y = num_y_ticks_above;
+    // inverse of convert_point_to_px
+    pub fn convert_px_to_point(&self, px: (f32, f32)) -> Point {
+        let (zero_x, zero_y) = self.zero_zero_px();
 
-        (min_y, max_y)
+        Point {
+            x: self.axes.x.value((px.0 - zero_x) / self.scale.x),
+            y: self.axes.y.value((zero_y - px.1) / self.scale.y),
+        }
     }
 
-    fn convert_point_to_px(&self, point: Point) -> (f32, f32) {
-        let zero_zero = self.zero_zero_px();
-        let (zero_x, zero_y) = zero_zero;
+    // shades between the curve and the x axis from a to b, blue above the axis and red below,
+    // and labels it with the signed area
+    pub fn shade_area<T: Calculate>(&mut self, equation: &T, a: f32, b: f32) {
+        let (start, end) = (a.min(b), a.max(b));
+        let (min_y, max_y) = self.get_y_range();
+        // keep the label near the screen for huge values
+        let clamp_y = |y: f32| y.clamp(min_y, max_y);
+
+        // about one sample per pixel, evenly spaced along the axis so a log axis is covered too
+        let (start_px, end_px) = (self.x_to_px(start), self.x_to_px(end));
+        if !start_px.is_finite() || !end_px.is_finite() {
+            return;
+        }
+        let steps = (end_px - start_px).ceil().clamp(1., 5000.) as i32;
+        let x_at = |i: i32| {
+            let px = start_px + (end_px - start_px) * i as f32 / steps as f32;
+            self.convert_px_to_point((px, 0.)).x
+        };
+
+        let mut above = Path::new();
+        let mut below = Path::new();
+        let mut region: Vec<Point> = Vec::new();
+
+        let mut prev_x = start;
+        let mut prev_y = equation.calc(start);
+        region.push(Point { x: start, y: 0. });
+        region.push(Point {
+            x: start,
+            y: prev_y,
+        });
+
+        for i in 1..=steps {
+            let x = x_at(i);
+            let y = equation.calc(x);
+
+            // close off the region where the curve crosses the axis
+            if (prev_y < 0.) != (y < 0.) && y != 0. {
+                let crossing_x = prev_x + (x - prev_x) * prev_y / (prev_y - y);
+                region.push(Point {
+                    x: crossing_x,
+                    y: 0.,
+                });
+                let path = if prev_y < 0. { &mut below } else { &mut above };
+                self.add_polygon(path, &region);
+                region.clear();
+                region.push(Point {
+                    x: crossing_x,
+                    y: 0.,
+                });
+            }
+
+            region.push(Point { x, y });
+            prev_x = x;
+            prev_y = y;
+        }
+
+        region.push(Point { x: end, y: 0. });
+        let path = if prev_y < 0. { &mut below } else { &mut above };
+        self.add_polygon(path, &region);
+
+        let mid_x = x_at(steps / 2);
 
-        let position_x = zero_x + (point.x * self.scale as f32);
-        let position_y = zero_y - (point.y * self.scale as f32);
+        self.surface
+            .fill_path(&above, &Paint::color(Color::rgba(0, 140, 255, 90)));
+        self.surface
+            .fill_path(&below, &Paint::color(Color::rgba(255, 60, 60, 90)));
 
-        (position_x, position_y)
+        // label halfway up the curve at the middle of the region
+        let label_point = Point {
+            x: mid_x,
+            y: clamp_y(equation.calc(mid_x)) / 2.,
+        };
+        let label_px = self.convert_point_to_px(label_point);
+        let area = equation.integrate(start, end);
+
+        let label_paint = Paint::color(Color::white())
+            .with_font_size(16.)
+            .with_text_align(Align::Center)
+            .with_text_baseline(Baseline::Middle);
+        self.surface.fill_text(
+            label_px.0,
+            label_px.1,
+            &format!("∫ = {}", format_value(area)),
+            &label_paint,
+        );
     }
 
-    // graph linear function using start and end points
-    fn graph_linear<T: Calculate>(&mut self, equation: &T) {
+    // keeps the path near the screen so huge values, or 0 on a log axis, don't distort the fill
+    fn add_polygon(&self, path: &mut Path, points: &[Point]) {
+        let (top, bottom) = (-CULL_MARGIN_PX, self.size.height + CULL_MARGIN_PX);
+        let clamp_px = |y: f32| {
+            if y.is_nan() {
+                bottom
+            } else {
+                y.clamp(top, bottom)
+            }
+        };
+
+        for (i, point) in points.iter().enumerate() {
+            let px = self.convert_point_to_px(*point);
+            let px = (px.0, clamp_px(px.1));
+            if i == 0 {
+                path.move_to(px.0, px.1);
+            } else {
+                path.line_to(px.0, px.1);
+            }
+        }
+        path.close();
+    }
+
+    // marks where the curve crosses or touches y = 0 in the visible range
+    // repeated roots are labeled with their multiplicity
+    pub fn mark_roots<T: Calculate>(&mut self, equation: &T) {
         let (min_x, max_x) = self.get_x_range();
 
-        let mut eq_path = Path::new();
+        for root in equation.roots_in(min_x, max_x) {
+            let label = match root.multiplicity {
+                1 => format!("x = {}", format_value(root.x)),
+                m => format!("x = {} (×{})", format_value(root.x), m),
+            };
+            let point = Point { x: root.x, y: 0. };
+            self.draw_labeled_point(point, &label, Color::rgb(255, 255, 0));
+        }
+    }
 
-        // linear - only need two points
-        for i in [min_x, max_x] {
-            let point = Point {
-                x: i as f32,
-                y: equation.calc(i as f32),
+    // marks local minima, maxima and inflection points, labeled with their coordinates
+    pub fn mark_critical_points(&mut self, points: &[CriticalPoint]) {
+        for critical in points {
+            let (name, color) = match critical.kind {
+                CriticalPointKind::LocalMinimum => ("min", Color::rgb(0, 220, 255)),
+                CriticalPointKind::LocalMaximum => ("max", Color::rgb(255, 80, 200)),
+                CriticalPointKind::Inflection => ("inflection", Color::rgb(220, 220, 220)),
             };
-            let point_px = self.convert_point_to_px(point);
+            let label = format!(
+                "{} ({}, {})",
+                name,
+                format_value(critical.point.x),
+                format_value(critical.point.y)
+            );
+            self.draw_labeled_point(critical.point, &label, color);
+        }
+    }
 
-            if eq_path.is_empty() {
-                eq_path.move_to(point_px.0, point_px.1);
-            } else {
-                eq_path.line_to(point_px.0, point_px.1);
+    // marks every point where two of the equations meet in the visible range
+    pub fn mark_intersections<T: Calculate>(&mut self, equations: &[&T]) {
+        let (min_x, max_x) = self.get_x_range();
+
+        for (i, first) in equations.iter().enumerate() {
+            for second in &equations[i + 1..] {
+                for point in first.intersections_with(second, min_x, max_x) {
+                    let label = format!("({}, {})", format_value(point.x), format_value(point.y));
+                    self.draw_labeled_point(point, &label, Color::rgb(255, 140, 0));
+                }
             }
         }
+    }
+
+    fn draw_labeled_point(&mut self, point: Point, label: &str, color: Color) {
+        let (x_px, y_px) = self.convert_point_to_px(point);
+        // eg: a root at y = 0 on a log axis
+        if !x_px.is_finite() || !y_px.is_finite() {
+            return;
+        }
 
-        let red_paint = Paint::color(Color::rgb(255, 0, 0));
-        self.canvas.stroke_path(&eq_path, &red_paint);
+        let mut marker = Path::new();
+        marker.circle(x_px, y_px, 4.);
+        self.surface.fill_path(&marker, &Paint::color(color));
+        self.surface
+            .stroke_path(&marker, &Paint::color(Color::black()).with_line_width(1.));
+
+        let label_paint = Paint::color(color)
+            .with_font_size(13.)
+            .with_text_baseline(Baseline::Top);
+        self.surface
+            .fill_text(x_px + 6., y_px + 6., label, &label_paint);
     }
 
-    // graphs a quadratic function by converting the function into a quadratic bezier curve
-    // p0 = starting point, p2 = ending point, p1 = (midx, 2*midy - (p0y+p2y)/2)
-    fn graph_quad<T: Calculate>(&mut self, equation: &T) {
-        let domain = self.get_x_range();
-        let min_x = domain.0 as f32;
-        let max_x = domain.1 as f32;
-
-        let p0 = Point {
-            x: min_x,
-            y: equation.calc(min_x),
-        };
-        let p2 = Point {
-            x: max_x,
-            y: equation.calc(max_x),
-        };
+    // the equation with a curve within radius pixels of the cursor, closest first,
+    // and the point on it the cursor snaps to
+    pub fn nearest_curve<T: Calculate>(
+        &self,
+        equations: &[&T],
+        cursor: (f32, f32),
+        radius: f32,
+    ) -> Option<(usize, Point)> {
+        equations
+            .iter()
+            .enumerate()
+            .filter_map(|(i, equation)| {
+                let curve_px = |x_px: f32| {
+                    let x = self.convert_px_to_point((x_px, 0.)).x;
+                    (x_px, self.y_to_px(equation.calc(x)))
+                };
+                let (px, distance) = sampling::closest_point(curve_px, cursor, radius)?;
+                Some((i, px, distance))
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(i, px, _)| {
+                let x = self.convert_px_to_point(px).x;
+                let y = equations[i].calc(x);
+                (i, Point { x, y })
+            })
+    }
 
-        let mid_x = (min_x + max_x) / 2.;
-        let mid_y = equation.calc(mid_x);
-        let p1_x = mid_x;
-        let p1_y = 2. * mid_y - 0.5 * (p0.y + p2.y);
-        let p1 = Point { x: p1_x, y: p1_y };
+    // lines across the screen through the cursor with its coordinates next to it
+    // snapped to a curve, it goes through the point on the curve instead
+    // and the coordinates are in a tooltip in the curve's color
+    pub fn draw_crosshair(&mut self, cursor: (f32, f32), snapped: Option<(Point, Color)>) {
+        let (point, (x_px, y_px)) = match snapped {
+            Some((point, _)) => (point, self.convert_point_to_px(point)),
+            None => (self.convert_px_to_point(cursor), cursor),
+        };
 
-        let p0_px = self.convert_point_to_px(p0);
-        let p1_px = self.convert_point_to_px(p1);
-        let p2_px = self.convert_point_to_px(p2);
+        let mut lines = Path::new();
+        lines.move_to(x_px, 0.);
+        lines.line_to(x_px, self.size.height);
+        lines.move_to(0., y_px);
+        lines.line_to(self.size.width, y_px);
+        self.surface.stroke_path(
+            &lines,
+            &Paint::color(Color::rgba(255, 255, 255, 90)).with_line_width(1.),
+        );
 
-        let mut path = Path::new();
+        let label = format!("({}, {})", format_value(point.x), format_value(point.y));
+        let padding = 4.;
+        let text_paint = Paint::color(Color::white())
+            .with_font_size(13.)
+            .with_text_baseline(Baseline::Top);
+        let (text_x, text_y) = (x_px + 8. + padding, y_px + 8. + padding);
 
-        path.move_to(p0_px.0, p0_px.1);
-        path.quad_to(p1_px.0, p1_px.1, p2_px.0, p2_px.1);
+        match snapped {
+            Some((_, color)) => {
+                let mut marker = Path::new();
+                marker.circle(x_px, y_px, 4.);
+                self.surface.fill_path(&marker, &Paint::color(color));
 
-        let paint = Paint::color(Color::rgbf(0., 0., 1.));
-        let mut points = Path::new();
-        points.circle(p0_px.0, p0_px.1, 5.);
-        points.circle(p1_px.0, p1_px.1, 5.);
-        points.circle(p2_px.0, p2_px.1, 5.);
+                let text_width = self.surface.measure_text(&label, &text_paint);
+                let mut tooltip = Path::new();
+                tooltip.rounded_rect(
+                    x_px + 8.,
+                    y_px + 8.,
+                    text_width + 2. * padding,
+                    13. + 2. * padding,
+                    3.,
+                );
+                self.surface
+                    .fill_path(&tooltip, &Paint::color(Color::rgba(0, 0, 0, 220)));
+                self.surface
+                    .stroke_path(&tooltip, &Paint::color(color).with_line_width(1.));
 
-        self.canvas.stroke_path(&path, &paint);
-        self.canvas.fill_path(&points, &paint);
+                self.surface
+                    .fill_text(text_x, text_y, &label, &text_paint.with_color(color));
+            }
+            None => {
+                self.surface.fill_text(
+                    text_x,
+                    text_y,
+                    &label,
+                    &text_paint.with_color(Color::rgb(180, 180, 180)),
+                );
+            }
+        }
     }
 
-    // graph polynomial of arbitrary degree using linear interpolation
-    fn graph_poly<T: Calculate>(&mut self, equation: &T) {
+    // parts of the x range where the curve is on screen, give or take a margin
+    // drawing only these keeps paths from reaching thousands of pixels off screen
+    fn visible_intervals<T: Calculate>(&self, equation: &T) -> Vec<(f32, f32)> {
         let (min_x, max_x) = self.get_x_range();
+        // sampling for the crossings is even in x, which misses most of a log x axis,
+        // so the sampler's own skipping of off-screen parts has to do there
+        if self.axes.x == AxisMode::Log {
+            return vec![(min_x, max_x)];
+        }
 
-        let capped_points_per_unit = if self.scale > 5000. {
-            5000.
-        } else {
-            self.scale
-        };
-        let ppu_rounded = capped_points_per_unit.round() as i32;
-        // set ppu based on scale so the performance is ok for small scale
-        // cap so peformance doesn't degrade at large scale where there is more off-screen graph
+        let bottom = self.convert_px_to_point((0., self.size.height + CULL_MARGIN_PX));
+        let top = self.convert_px_to_point((0., -CULL_MARGIN_PX));
+        let band = (bottom.y, top.y);
 
-        let loop_bounds = ((min_x * ppu_rounded), (max_x * ppu_rounded));
+        let mut crossings = equation.solve(band.0, min_x, max_x);
+        crossings.extend(equation.solve(band.1, min_x, max_x));
 
+        sampling::visible_intervals(|x| equation.calc(x), crossings, (min_x, max_x), band)
+    }
+
+    // graph linear function using start and end points
+    fn graph_linear<T: Calculate>(&mut self, equation: &T, paint: &Paint) {
         let mut eq_path = Path::new();
 
-        for i in loop_bounds.0..loop_bounds.1 {
-            let x = i as f32 / ppu_rounded as f32;
+        // linear - only need two points
+        for (start, end) in self.visible_intervals(equation) {
+            let start_px = self.convert_point_to_px(Point {
+                x: start,
+                y: equation.calc(start),
+            });
+            let end_px = self.convert_point_to_px(Point {
+                x: end,
+                y: equation.calc(end),
+            });
+
+            eq_path.move_to(start_px.0, start_px.1);
+            eq_path.line_to(end_px.0, end_px.1);
+        }
+
+        self.surface.stroke_path(&eq_path, paint);
+    }
 
-            let point = Point {
-                x,
-                y: equation.calc(x),
+    // graphs a quadratic function by converting the function into a quadratic bezier curve
+    // p0 = starting point, p2 = ending point, p1 = (midx, 2*midy - (p0y+p2y)/2)
+    fn graph_quad<T: Calculate>(&mut self, equation: &T, paint: &Paint) {
+        let mut path = Path::new();
+
+        for (min_x, max_x) in self.visible_intervals(equation) {
+            let p0 = Point {
+                x: min_x,
+                y: equation.calc(min_x),
+            };
+            let p2 = Point {
+                x: max_x,
+                y: equation.calc(max_x),
             };
-            let point_px = self.convert_point_to_px(point);
 
-            if eq_path.is_empty() {
-                eq_path.move_to(point_px.0, point_px.1);
-            } else {
-                eq_path.line_to(point_px.0, point_px.1);
-            }
+            let mid_x = (min_x + max_x) / 2.;
+            let mid_y = equation.calc(mid_x);
+            let p1_x = mid_x;
+            let p1_y = 2. * mid_y - 0.5 * (p0.y + p2.y);
+            let p1 = Point { x: p1_x, y: p1_y };
+
+            let p0_px = self.convert_point_to_px(p0);
+            let p1_px = self.convert_point_to_px(p1);
+            let p2_px = self.convert_point_to_px(p2);
+
+            path.move_to(p0_px.0, p0_px.1);
+            path.quad_to(p1_px.0, p1_px.1, p2_px.0, p2_px.1);
+        }
+
+        self.surface.stroke_path(&path, paint);
+    }
+
+    // graphs a cubic function as a cubic bezier curve for each visible part, which is exact
+    fn graph_cubic<T: Calculate>(&mut self, equation: &T, paint: &Paint) {
+        let mut path = Path::new();
+        for (start, end) in self.visible_intervals(equation) {
+            self.add_cubic(&mut path, equation, start, end, false);
         }
 
-        let red_paint = Paint::color(Color::rgb(255, 0, 0));
-        self.canvas.stroke_path(&eq_path, &red_paint);
+        self.surface.stroke_path(&path, paint);
     }
 
-    pub fn graph_equation<T: Calculate + CouldBeLinear + CouldBeQuad>(&mut self, equation: &T) {
-        if equation.is_linear() {
-            self.graph_linear(equation);
+    // graph polynomial of arbitrary degree as cubic bezier pieces, each within a fraction of a pixel
+    // falls back to sampling when the equation can't say how far off a cubic would be
+    fn graph_poly<T: Calculate>(&mut self, equation: &T, paint: &Paint) {
+        let (min_x, max_x) = self.get_x_range();
+
+        if equation.cubic_error_bound(min_x, max_x).is_none() {
+            self.graph_sampled(equation, paint);
             return;
+        }
+
+        let mut path = Path::new();
+        for interval in self.visible_intervals(equation) {
+            let mut budget = self.samples_left;
+            let breaks = sampling::cubic_breaks(
+                |a, b| equation.cubic_error_bound(a, b).unwrap_or(f32::INFINITY) * self.scale.y,
+                interval,
+                &mut budget,
+            );
+            self.samples_left = budget;
+
+            let mut joined = false;
+            for pair in breaks.windows(2) {
+                joined = self.add_cubic(&mut path, equation, pair[0], pair[1], joined);
+            }
+        }
+
+        self.surface.stroke_path(&path, paint);
+    }
+
+    // adds the cubic bezier with the same values and slopes as the curve at a and b
+    // a cubic curve is matched exactly, anything else is off by at most its cubic_error_bound
+    // control points are a third of the way along each tangent
+    // joined carries on from the last piece instead of starting a new one, returns whether it drew
+    fn add_cubic<T: Calculate>(
+        &self,
+        path: &mut Path,
+        equation: &T,
+        a: f32,
+        b: f32,
+        joined: bool,
+    ) -> bool {
+        let third = (b - a) / 3.;
+        let (y_a, y_b) = (equation.calc(a), equation.calc(b));
+
+        let p0 = self.convert_point_to_px(Point { x: a, y: y_a });
+        let p1 = self.convert_point_to_px(Point {
+            x: a + third,
+            y: y_a + third * equation.slope(a),
+        });
+        let p2 = self.convert_point_to_px(Point {
+            x: b - third,
+            y: y_b - third * equation.slope(b),
+        });
+        let p3 = self.convert_point_to_px(Point { x: b, y: y_b });
+
+        // values too big for f32 can't be drawn
+        if ![p0, p1, p2, p3]
+            .iter()
+            .all(|p| p.0.is_finite() && p.1.is_finite())
+        {
+            return false;
+        }
+
+        if !joined {
+            path.move_to(p0.0, p0.1);
+        }
+        path.bezier_to(p1.0, p1.1, p2.0, p2.1, p3.0, p3.1);
+        true
+    }
+
+    // graph any equation using linear interpolation
+    fn graph_sampled<T: Calculate>(&mut self, equation: &T, paint: &Paint) {
+        let strips = self.sampled_strips(equation);
+        self.stroke_strips(&strips, paint);
+    }
+
+    // sampled adaptively, so there are more points where the curve bends on screen
+    // samples are spread evenly along the x axis as drawn, so a log axis gets as many per decade
+    fn sampled_strips<T: Calculate>(&mut self, equation: &T) -> Vec<Vec<(f32, f32)>> {
+        let x_axis = self.axes.x;
+        let mut all_strips = vec![];
+
+        for (start, end) in self.visible_intervals(equation) {
+            let mut budget = self.samples_left;
+            let strips = sampling::sample_curve(
+                |position| equation.calc(x_axis.value(position)),
+                |position, y| {
+                    self.convert_point_to_px(Point {
+                        x: x_axis.value(position),
+                        y,
+                    })
+                },
+                (x_axis.position(start), x_axis.position(end)),
+                self.size.height,
+                &mut budget,
+            );
+            self.samples_left = budget;
+            all_strips.extend(strips);
+        }
+
+        all_strips
+    }
+
+    fn stroke_strips(&mut self, strips: &[Vec<(f32, f32)>], paint: &Paint) {
+        let mut path = Path::new();
+        for strip in strips {
+            path.move_to(strip[0].0, strip[0].1);
+            for (x, y) in &strip[1..] {
+                path.line_to(*x, *y);
+            }
+        }
+
+        self.surface.stroke_path(&path, paint);
+    }
+
+    // draws the equation in its style, dashed curves are sampled so they can be cut into dashes
+    pub fn graph_equation<T: Calculate + CouldBeLinear + CouldBeQuad + CouldBeCubic>(
+        &mut self,
+        equation: &T,
+        style: &Style,
+    ) {
+        match style.dash.pattern() {
+            None => self.graph_equation_with_paint(equation, &style.paint()),
+            Some(pattern) => {
+                let strips = self.sampled_strips(equation);
+                self.stroke_strips(&style::dash_strips(&strips, pattern), &style.paint());
+            }
+        }
+    }
+
+    // solid curves drawn with the given paint, as exact lines and beziers where possible
+    pub fn graph_equation_with_paint<T: Calculate + CouldBeLinear + CouldBeQuad + CouldBeCubic>(
+        &mut self,
+        equation: &T,
+        paint: &Paint,
+    ) {
+        // lines and beziers are only straight or exact when both axes are linear
+        if self.axes.any_log() {
+            self.graph_sampled(equation, paint);
+        } else if equation.is_linear() {
+            self.graph_linear(equation, paint);
         } else if equation.is_quadratic() {
-            self.graph_quad(equation);
-            return;
+            self.graph_quad(equation, paint);
+        } else if equation.is_cubic() {
+            self.graph_cubic(equation, paint);
         } else {
-            self.graph_poly(equation);
+            self.graph_poly(equation, paint);
+        }
+    }
+
+    // a box in the top left corner with each equation's text in its color,
+    // after a short line in its style so dashed curves can be told apart too
+    pub fn draw_legend(&mut self, entries: &[(String, Style)]) {
+        if entries.is_empty() {
+            return;
+        }
+
+        let (margin, padding) = (10., 8.);
+        let (line_height, sample_width) = (20., 24.);
+        let text_paint = Paint::color(Color::white())
+            .with_font_size(14.)
+            .with_text_baseline(Baseline::Middle);
+
+        let text_width = entries
+            .iter()
+            .map(|(text, _)| self.surface.measure_text(text, &text_paint))
+            .fold(0., f32::max);
+
+        let mut background = Path::new();
+        background.rounded_rect(
+            margin,
+            margin,
+            sample_width + text_width + 3. * padding,
+            entries.len() as f32 * line_height + padding,
+            4.,
+        );
+        self.surface
+            .fill_path(&background, &Paint::color(Color::rgba(0, 0, 0, 200)));
+        self.surface.stroke_path(
+            &background,
+            &Paint::color(Color::rgb(90, 90, 90)).with_line_width(1.),
+        );
+
+        for (i, (text, style)) in entries.iter().enumerate() {
+            let x = margin + padding;
+            let y = margin + padding / 2. + (i as f32 + 0.5) * line_height;
+
+            let sample = vec![vec![(x, y), (x + sample_width, y)]];
+            match style.dash.pattern() {
+                None => self.stroke_strips(&sample, &style.paint()),
+                Some(pattern) => {
+                    self.stroke_strips(&style::dash_strips(&sample, pattern), &style.paint())
+                }
+            }
+
+            let paint = text_paint.clone().with_color(style.color);
+            self.surface
+                .fill_text(x + sample_width + padding, y, text, &paint);
         }
     }
 }
+
+// the fitted points are kept this fraction of the screen in from each edge
+const FIT_MARGIN: f32 = 0.1;
+
+// the scale and offset that puts all the points on a screen of the given size
+// each axis is fitted on its own unless equal_aspect, then the tighter one is used for both
+// an axis where the points are all the same is given one unit, or decade, each side of them
+pub fn fit_view(
+    points: &[Point],
+    size: LogicalSize<f32>,
+    axes: AxisModes,
+    equal_aspect: bool,
+) -> Option<(Scale, LogicalPosition<f32>)> {
+    let positions: Vec<(f32, f32)> = points
+        .iter()
+        .map(|point| (axes.x.position(point.x), axes.y.position(point.y)))
+        .filter(|(x, y)| x.is_finite() && y.is_finite())
+        .collect();
+    if positions.is_empty() {
+        return None;
+    }
+
+    // (low, high) along one axis, with at least a unit either side of the middle
+    let bounds = |values: &mut dyn Iterator<Item = f32>| {
+        let (low, high) = values.fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), v| {
+            (low.min(v), high.max(v))
+        });
+        let (middle, half) = ((low + high) / 2., ((high - low) / 2.).max(1.));
+        (middle - half, middle + half)
+    };
+    let x_bounds = bounds(&mut positions.iter().map(|p| p.0));
+    let y_bounds = bounds(&mut positions.iter().map(|p| p.1));
+
+    let usable = 1. - 2. * FIT_MARGIN;
+    let mut scale = Scale {
+        x: size.width * usable / (x_bounds.1 - x_bounds.0),
+        y: size.height * usable / (y_bounds.1 - y_bounds.0),
+    };
+    if equal_aspect {
+        scale = Scale::uniform(scale.x.min(scale.y));
+    }
+
+    // the middle of the points goes in the middle of the screen
+    let offset = LogicalPosition::new(
+        -(x_bounds.0 + x_bounds.1) / 2. * scale.x,
+        (y_bounds.0 + y_bounds.1) / 2. * scale.y,
+    );
+    Some((scale, offset))
+}
+
+// major gridlines are at least this far apart
+const MIN_MAJOR_SPACING_PX: f32 = 80.;
+
+// (major, minor) gridline spacing in units for the scale in pixels per unit
+// major is the smallest 1, 2 or 5 times a power of ten that's far enough apart
+fn grid_spacing(scale: f32) -> (f32, f32) {
+    let raw = MIN_MAJOR_SPACING_PX / scale;
+    let power = 10_f32.powf(raw.log10().floor());
+
+    match raw / power {
+        m if m <= 1. => (power, power / 5.),
+        m if m <= 2. => (2. * power, power / 2.),
+        m if m <= 5. => (5. * power, power),
+        _ => (10. * power, 2. * power),
+    }
+}
+
+// a log axis only gets gridlines between powers of ten once the decades are this far apart
+const MIN_DECADE_PX_FOR_MINOR: f32 = 120.;
+
+// the smallest and biggest powers of ten an f32 has, counting subnormals
+const MIN_DECADE: f32 = -45.;
+const MAX_DECADE: f32 = 38.;
+
+// (value, is major) for every gridline from low to high
+// linear axes use the 1-2-5 spacing, log axes put major lines on powers of ten
+// and minor ones on the multiples in between, skipping decades when they're squeezed together
+fn gridlines(mode: AxisMode, scale: f32, low: f32, high: f32) -> Vec<(f32, bool)> {
+    match mode {
+        AxisMode::Linear => {
+            let (major, minor) = grid_spacing(scale);
+            // 4 or 5 minor steps per major one
+            let per_major = (major / minor).round() as i64;
+            grid_indices(low, high, minor)
+                .map(|i| (i as f32 * minor, i % per_major == 0))
+                .collect()
+        }
+        AxisMode::Log => {
+            // decades between major lines, at least 1
+            let step = grid_spacing(scale).0.max(1.).round() as i64;
+            let with_minor = step == 1 && scale >= MIN_DECADE_PX_FOR_MINOR;
+            // zoomed far out the range under or overflows, so only decades f32 can hold are drawn
+            let lowest = low.log10().floor().max(MIN_DECADE);
+            let highest = high.log10().floor().min(MAX_DECADE);
+            let decades = lowest as i64..=highest as i64;
+
+            let mut lines = vec![];
+            for decade in decades {
+                let power = 10_f32.powi(decade as i32);
+                if decade % step == 0 {
+                    lines.push((power, true));
+                }
+                if with_minor {
+                    lines.extend((2..10).map(|m| (m as f32 * power, false)));
+                }
+            }
+            lines.retain(|(value, _)| *value >= low && *value <= high);
+            lines
+        }
+    }
+}
+
+// the label for a major gridline, powers of ten far from 1 are written like 1e6
+fn tick_label(mode: AxisMode, scale: f32, value: f32) -> String {
+    match mode {
+        AxisMode::Linear => format_tick(value, grid_spacing(scale).0),
+        AxisMode::Log => {
+            let decade = value.log10().round() as i32;
+            if decade.abs() <= 4 {
+                format_tick(value, value)
+            } else {
+                format!("1e{}", decade)
+            }
+        }
+    }
+}
+
+// multiples of step from low to high, as the multiplier so gridlines don't drift from rounding
+fn grid_indices(low: f32, high: f32, step: f32) -> std::ops::RangeInclusive<i64> {
+    (low / step).ceil() as i64..=(high / step).floor() as i64
+}
+
+// just enough decimal places to tell gridlines apart, eg: one for steps of 0.5
+fn format_tick(value: f32, step: f32) -> String {
+    let decimals = (-step.log10().floor()).max(0.) as usize;
+    let formatted = format!("{:.*}", decimals, value);
+
+    match formatted
+        .trim_start_matches('-')
+        .trim_matches(|c| c == '0' || c == '.')
+    {
+        "" => String::from("0"),
+        _ => formatted,
+    }
+}
+
+// up to 3 decimal places without trailing zeros, eg: 2, 0.5, -1.333
+fn format_value(value: f32) -> String {
+    let formatted = format!("{:.3}", value);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+
+    match trimmed {
+        "-0" => String::from("0"),
+        _ => String::from(trimmed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grapher::drawing::recording::{DrawCall, Recording};
+    use crate::grapher::drawing::PathCommand;
+    use crate::grapher::equation::Polynomial;
+    use crate::grapher::style::Dash;
+
+    #[test]
+    fn draws_on_a_recording() {
+        let mut recording = Recording::new();
+        let size = LogicalSize::new(1000., 600.);
+        let mut graph = Graph::new(
+            size,
+            Scale::uniform(50.),
+            LogicalPosition::default(),
+            &mut recording,
+        );
+
+        let cubic = Polynomial::x().pow(3) - Polynomial::x();
+        let solid = Style::from_palette(0);
+        let dashed = Style {
+            dash: Dash::Dashed,
+            ..Style::from_palette(1)
+        };
+        graph.init_graph();
+        graph.graph_equation(&cubic, &solid);
+        graph.graph_equation(&cubic, &dashed);
+
+        let strokes: Vec<(&Path, &Paint)> = recording
+            .calls
+            .iter()
+            .filter_map(|call| match call {
+                DrawCall::Stroke(path, paint) => Some((path, paint)),
+                _ => None,
+            })
+            .collect();
+        let texts: Vec<&str> = recording
+            .calls
+            .iter()
+            .filter_map(|call| match call {
+                DrawCall::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        // the x axis goes across the middle
+        let axes = strokes[0].0.commands();
+        assert_eq!(
+            axes[..2],
+            [
+                PathCommand::MoveTo(0., 300.),
+                PathCommand::LineTo(1000., 300.)
+            ]
+        );
+        assert!(texts.contains(&"2") && texts.contains(&"-4"), "{:?}", texts);
+        // x = -10 and 10 and y = -6 and 6 are on the edges, where they'd be cut off
+        assert!(
+            !texts.contains(&"-10") && !texts.contains(&"10"),
+            "{:?}",
+            texts
+        );
+        assert_eq!(
+            texts.iter().filter(|t| **t == "6").count(),
+            1,
+            "{:?}",
+            texts
+        );
+
+        // a cubic is drawn with beziers, unless it's dashed
+        let curve = |color| {
+            strokes
+                .iter()
+                .find(|(_, paint)| paint.color == color)
+                .unwrap()
+                .0
+                .commands()
+        };
+        assert!(curve(solid.color)
+            .iter()
+            .any(|c| matches!(c, PathCommand::BezierTo(..))));
+        let dashes = curve(dashed.color)
+            .iter()
+            .filter(|c| matches!(c, PathCommand::MoveTo(..)))
+            .count();
+        assert!(dashes > 10, "{}", dashes);
+
+        // a quadratic is only its curve, there aren't any dots on its control points
+        let quad = Style::from_palette(2);
+        let mut recording = Recording::new();
+        let mut graph = Graph::new(
+            size,
+            Scale::uniform(50.),
+            LogicalPosition::default(),
+            &mut recording,
+        );
+        graph.graph_equation(&(Polynomial::x().pow(2) - Polynomial::constant(1.)), &quad);
+        assert!(!recording
+            .calls
+            .iter()
+            .any(|call| matches!(call, DrawCall::Fill(..))));
+    }
+
+    #[test]
+    fn grid_spacing_is_one_two_or_five() {
+        assert_eq!(grid_spacing(50.), (2., 0.5));
+        assert_eq!(grid_spacing(80.), (1., 0.2));
+        assert_eq!(grid_spacing(20.), (5., 1.));
+        assert_eq!(grid_spacing(1.), (100., 20.));
+        let (major, minor) = grid_spacing(40_000.);
+        assert!((major - 0.002).abs() < 1e-9 && (minor - 0.0005).abs() < 1e-9);
+    }
+
+    #[test]
+    fn tick_labels_have_enough_decimals() {
+        assert_eq!(format_tick(4., 2.), "4");
+        assert_eq!(format_tick(0.5, 0.5), "0.5");
+        assert_eq!(format_tick(0.004, 0.002), "0.004");
+        assert_eq!(format_tick(-0.0001, 0.5), "0");
+        assert_eq!(format_tick(1500., 500.), "1500");
+    }
+
+    #[test]
+    fn log_gridlines_are_on_decades() {
+        let lines = gridlines(AxisMode::Log, 100., 0.5, 2000.);
+        assert_eq!(
+            lines,
+            vec![(1., true), (10., true), (100., true), (1000., true)]
+        );
+
+        // wide decades get the multiples in between
+        let lines = gridlines(AxisMode::Log, 200., 1., 30.);
+        let minor: Vec<f32> = lines.iter().filter(|l| !l.1).map(|l| l.0).collect();
+        assert_eq!(minor.len(), 8 + 2);
+        assert_eq!(minor[0], 2.);
+
+        // a range that's under and overflowed still has a handful of lines
+        let lines = gridlines(AxisMode::Log, 1e-3, 0., f32::INFINITY);
+        assert!(lines.len() <= (MAX_DECADE - MIN_DECADE) as usize + 1);
+        let lines = gridlines(AxisMode::Log, 100., 0., f32::INFINITY);
+        assert_eq!(lines.len(), (MAX_DECADE - MIN_DECADE) as usize + 1);
+
+        assert_eq!(tick_label(AxisMode::Log, 50., 0.01), "0.01");
+        assert_eq!(tick_label(AxisMode::Log, 50., 1e6), "1e6");
+        assert!(AxisMode::Log.position(-1.).is_nan());
+        assert!(AxisMode::Log.position(0.).is_nan());
+    }
+
+    #[test]
+    fn fitted_points_are_on_screen() {
+        let size = LogicalSize::new(1000., 600.);
+        let points = [Point { x: -2., y: 0. }, Point { x: 8., y: 30. }];
+
+        let (scale, offset) = fit_view(&points, size, AxisModes::default(), false).unwrap();
+        assert_eq!(scale, Scale { x: 80., y: 16. });
+        // the middle, (3, 15), is at the center
+        assert_eq!((offset.x, offset.y), (-240., 240.));
+
+        let (scale, _) = fit_view(&points, size, AxisModes::default(), true).unwrap();
+        assert_eq!(scale, Scale::uniform(16.));
+
+        // a single point gets a unit each side
+        let (scale, _) = fit_view(&points[..1], size, AxisModes::default(), false).unwrap();
+        assert_eq!(scale, Scale { x: 400., y: 240. });
+        assert!(fit_view(&[], size, AxisModes::default(), false).is_none());
+    }
+}
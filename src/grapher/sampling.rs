@@ -0,0 +1,348 @@
+// adaptive sampling for drawing curves as line strips or cubic beziers
+// a segment is split in half while the curve bends away from the line or cubic between its ends
+// by more than the tolerance, so flat parts get a few points and steep or curvy parts get many
+
+// how far the curve can be from the drawn line, in pixels
+const TOLERANCE_PX: f32 = 0.25;
+// the first pass samples every this many pixels, fine enough not to step over whole wiggles
+const INITIAL_SEGMENT_PX: f32 = 8.;
+// segments stop splitting after this many halvings even if they're still off, eg: at a pole
+const MAX_DEPTH: u32 = 16;
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    x: f32,
+    px: (f32, f32),
+}
+
+impl Sample {
+    fn is_finite(&self) -> bool {
+        self.px.0.is_finite() && self.px.1.is_finite()
+    }
+}
+
+// pixel distance of the middle sample from the line between the outer two
+fn chord_error(left: Sample, mid: Sample, right: Sample) -> f32 {
+    let (dx, dy) = (right.px.0 - left.px.0, right.px.1 - left.px.1);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length == 0. {
+        return 0.;
+    }
+    ((mid.px.0 - left.px.0) * dy - (mid.px.1 - left.px.1) * dx).abs() / length
+}
+
+fn needs_split(left: Sample, mid: Sample, right: Sample, height: f32) -> bool {
+    let finite = [left, mid, right].iter().filter(|s| s.is_finite()).count();
+    match finite {
+        // nothing to draw
+        0 => return false,
+        // the edge of the domain, eg: sqrt(x) at 0, is somewhere in here
+        1 | 2 => return true,
+        _ => {}
+    }
+
+    // off the same edge of the screen, any error there can't be seen
+    let ys = [left.px.1, mid.px.1, right.px.1];
+    if ys.iter().all(|y| *y < 0.) || ys.iter().all(|y| *y > height) {
+        return false;
+    }
+
+    // a steep line can be close to its chord while the curve turns back past the ends,
+    // which is either a peak that needs more points or a pole
+    let (low, high) = (left.px.1.min(right.px.1), left.px.1.max(right.px.1));
+    if mid.px.1 < low - TOLERANCE_PX || mid.px.1 > high + TOLERANCE_PX {
+        return true;
+    }
+
+    chord_error(left, mid, right) > TOLERANCE_PX
+}
+
+// samples f over the x range so the lines between samples stay within the tolerance of the curve
+// returns strips of pixel positions, split where the curve isn't defined or jumps, eg: tan(x)
+// every evaluation is taken from budget and splitting stops once it runs out
+pub fn sample_curve(
+    f: impl Fn(f32) -> f32,
+    to_px: impl Fn(f32, f32) -> (f32, f32),
+    x_range: (f32, f32),
+    height: f32,
+    budget: &mut usize,
+) -> Vec<Vec<(f32, f32)>> {
+    let sample = |x: f32| Sample {
+        x,
+        px: to_px(x, f(x)),
+    };
+
+    let (start, end) = x_range;
+    let width_px = (to_px(end, 0.).0 - to_px(start, 0.).0).abs();
+    let segments = (width_px / INITIAL_SEGMENT_PX).ceil().max(1.) as usize;
+
+    let mut samples: Vec<Sample> = (0..=segments)
+        .map(|i| sample(start + (end - start) * i as f32 / segments as f32))
+        .collect();
+    *budget = budget.saturating_sub(samples.len());
+    // whether the segment starting at each sample is close enough to the curve
+    let mut settled = vec![false; segments];
+
+    // splitting a pass at a time spreads the budget evenly instead of using it all on the left
+    for _ in 0..MAX_DEPTH {
+        let mut next_samples = vec![samples[0]];
+        let mut next_settled = Vec::with_capacity(settled.len());
+        let mut split_any = false;
+
+        for (pair, done) in samples.windows(2).zip(&settled) {
+            let (left, right) = (pair[0], pair[1]);
+            let mid_x = (left.x + right.x) / 2.;
+
+            if *done || *budget == 0 || mid_x <= left.x || mid_x >= right.x {
+                next_settled.push(*done);
+            } else {
+                *budget -= 1;
+                let mid = sample(mid_x);
+                let split = needs_split(left, mid, right, height);
+                split_any |= split;
+
+                // the middle sample is already paid for, so it's kept either way
+                next_samples.push(mid);
+                next_settled.extend([!split, !split]);
+            }
+            next_samples.push(right);
+        }
+
+        samples = next_samples;
+        settled = next_settled;
+        if !split_any {
+            break;
+        }
+    }
+
+    let mut strips = vec![];
+    let mut strip: Vec<(f32, f32)> = vec![];
+    for (i, s) in samples.iter().enumerate() {
+        // still unsettled after a sub-pixel step while jumping a screen height is a discontinuity
+        let jumped = i > 0 && !settled[i - 1] && {
+            let previous = samples[i - 1];
+            (s.px.0 - previous.px.0).abs() < 1. && (s.px.1 - previous.px.1).abs() > height
+        };
+
+        if !s.is_finite() || jumped {
+            if strip.len() > 1 {
+                strips.push(std::mem::take(&mut strip));
+            }
+            strip.clear();
+        }
+        if s.is_finite() {
+            strip.push(s.px);
+        }
+    }
+    if strip.len() > 1 {
+        strips.push(strip);
+    }
+
+    strips
+}
+
+// splits the x range until a cubic between each pair of breaks is within the tolerance
+// error_px is the most the cubic on [a, b] can be off by in pixels, infinite if it's unknown
+// returns the x values to break at, including both ends
+pub fn cubic_breaks(
+    error_px: impl Fn(f32, f32) -> f32,
+    x_range: (f32, f32),
+    budget: &mut usize,
+) -> Vec<f32> {
+    let mut breaks = vec![x_range.0, x_range.1];
+    let mut settled = vec![false];
+
+    for _ in 0..MAX_DEPTH {
+        let mut next_breaks = vec![breaks[0]];
+        let mut next_settled = Vec::with_capacity(settled.len());
+        let mut split_any = false;
+
+        for (pair, done) in breaks.windows(2).zip(&settled) {
+            let (a, b) = (pair[0], pair[1]);
+            let mid = (a + b) / 2.;
+
+            if *done || *budget == 0 || mid <= a || mid >= b {
+                next_settled.push(true);
+            } else {
+                *budget -= 1;
+                let error = error_px(a, b);
+                // overflowing values can't be fixed by splitting
+                if error.is_finite() && error > TOLERANCE_PX {
+                    split_any = true;
+                    next_breaks.push(mid);
+                    next_settled.extend([false, false]);
+                } else {
+                    next_settled.push(true);
+                }
+            }
+            next_breaks.push(b);
+        }
+
+        breaks = next_breaks;
+        settled = next_settled;
+        if !split_any {
+            break;
+        }
+    }
+
+    breaks
+}
+
+// the parts of the x range where f is inside the band of y values, merged where they touch
+// crossings are where f meets either edge of the band, so between them it's all in or all out
+// a few points inside each piece are checked so a value that isn't defined at one x can't hide it
+pub fn visible_intervals(
+    f: impl Fn(f32) -> f32,
+    mut crossings: Vec<f32>,
+    x_range: (f32, f32),
+    band: (f32, f32),
+) -> Vec<(f32, f32)> {
+    let (start, end) = x_range;
+    crossings.retain(|x| *x > start && *x < end);
+    crossings.extend([start, end]);
+    crossings.sort_by(|a, b| a.total_cmp(b));
+    crossings.dedup();
+
+    let checks = 8;
+    let mut intervals: Vec<(f32, f32)> = vec![];
+    for pair in crossings.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let inside = (1..checks)
+            .map(|i| f(a + (b - a) * i as f32 / checks as f32))
+            .any(|y| y >= band.0 && y <= band.1);
+        if !inside {
+            continue;
+        }
+
+        match intervals.last_mut() {
+            Some(last) if last.1 == a => last.1 = b,
+            _ => intervals.push((a, b)),
+        }
+    }
+
+    intervals
+}
+
+// the point on the curve closest to the cursor if it's within radius pixels, with its distance
+// curve_px gives the curve's pixel position at a pixel x, and only x within radius can be close enough
+pub fn closest_point(
+    curve_px: impl Fn(f32) -> (f32, f32),
+    cursor: (f32, f32),
+    radius: f32,
+) -> Option<((f32, f32), f32)> {
+    // quarter pixel steps
+    let steps = (radius * 4.).ceil().max(1.) as i32;
+
+    (-steps..=steps)
+        .map(|i| curve_px(cursor.0 + radius * i as f32 / steps as f32))
+        .filter(|px| px.0.is_finite() && px.1.is_finite())
+        .map(|px| {
+            (
+                px,
+                ((px.0 - cursor.0).powi(2) + (px.1 - cursor.1).powi(2)).sqrt(),
+            )
+        })
+        .filter(|(_, distance)| *distance <= radius)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 50 pixels per unit with the origin at the top left, y down like the screen
+    fn to_px(x: f32, y: f32) -> (f32, f32) {
+        (x * 50., 300. - y * 50.)
+    }
+
+    #[test]
+    fn straight_lines_need_no_extra_samples() {
+        let mut budget = 10_000;
+        let strips = sample_curve(|x| 2. * x + 1., to_px, (0., 10.), 600., &mut budget);
+
+        assert_eq!(strips.len(), 1);
+        // the 64 points of the initial 8 pixel grid plus one checked midpoint per segment
+        assert_eq!(strips[0].len(), 64 + 63);
+    }
+
+    #[test]
+    fn curves_are_within_tolerance() {
+        let f = |x: f32| (3. * x).sin() * 2.;
+        let mut budget = 100_000;
+        let strips = sample_curve(f, to_px, (0., 10.), 600., &mut budget);
+
+        assert_eq!(strips.len(), 1);
+        for pair in strips[0].windows(2) {
+            let mid_x = (pair[0].0 + pair[1].0) / 2. / 50.;
+            let mid = to_px(mid_x, f(mid_x));
+            let line_y = (pair[0].1 + pair[1].1) / 2.;
+            assert!((mid.1 - line_y).abs() < 1., "{:?}", pair);
+        }
+    }
+
+    #[test]
+    fn stops_at_the_budget() {
+        let mut budget = 300;
+        let strips = sample_curve(|x| (20. * x).sin(), to_px, (0., 10.), 600., &mut budget);
+
+        assert_eq!(budget, 0);
+        let points: usize = strips.iter().map(|s| s.len()).sum();
+        assert!(points <= 300 + 64, "{}", points);
+    }
+
+    #[test]
+    fn breaks_at_poles_and_outside_the_domain() {
+        let mut budget = 100_000;
+        let tan = sample_curve(|x| x.tan(), to_px, (0., 3.), 600., &mut budget);
+        // tan(x) jumps from +inf to -inf at pi/2
+        assert_eq!(tan.len(), 2);
+
+        let sqrt = sample_curve(|x| x.sqrt(), to_px, (-2., 2.), 600., &mut budget);
+        assert_eq!(sqrt.len(), 1);
+        assert!(sqrt[0][0].0 < 1., "{:?}", sqrt[0][0]);
+    }
+
+    #[test]
+    fn cubic_breaks_split_until_within_tolerance() {
+        // a made up bound that shrinks like h^4, as the hermite error does
+        let error_px = |a: f32, b: f32| (b - a).powi(4);
+        let mut budget = 10_000;
+
+        let breaks = cubic_breaks(error_px, (0., 4.), &mut budget);
+
+        // 4 / 2^3 = 0.5 is the first width with 0.5^4 <= 0.25
+        assert_eq!(breaks, vec![0., 0.5, 1., 1.5, 2., 2.5, 3., 3.5, 4.]);
+        assert_eq!(cubic_breaks(|_, _| 0., (0., 4.), &mut budget), vec![0., 4.]);
+    }
+
+    #[test]
+    fn visible_intervals_skip_off_screen_parts() {
+        // x^2 is inside [-1, 4] for |x| <= 2
+        let square = |x: f32| x * x;
+        let crossings = vec![-2., 2.];
+
+        assert_eq!(
+            visible_intervals(square, crossings, (-10., 10.), (-1., 4.)),
+            vec![(-2., 2.)]
+        );
+        // with the curve on screen all the way across nothing is cut
+        assert_eq!(
+            visible_intervals(square, vec![], (-1., 1.), (-1., 4.)),
+            vec![(-1., 1.)]
+        );
+        assert!(visible_intervals(|_| 10., vec![], (-1., 1.), (-1., 4.)).is_empty());
+    }
+
+    #[test]
+    fn closest_point_is_across_not_straight_down() {
+        // steep enough that the nearest point isn't the one right above or below the cursor
+        let steep = |x: f32| (x, 2. * x);
+
+        let (px, distance) = closest_point(steep, (10., 22.), 5.).unwrap();
+        assert!(px.0 > 10.5, "{:?}", px);
+        assert!(distance < 1., "{}", distance);
+
+        assert!(closest_point(steep, (10., 40.), 5.).is_none());
+        assert!(closest_point(|x| (x, f32::NAN), (10., 20.), 5.).is_none());
+    }
+}
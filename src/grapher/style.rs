@@ -0,0 +1,150 @@
+use super::drawing::{Color, LineCap, Paint};
+
+// distinct on black and away from the green grid, the markers use their own colors
+const PALETTE: [(u8, u8, u8); 8] = [
+    (255, 70, 70),
+    (70, 140, 255),
+    (255, 210, 60),
+    (220, 90, 255),
+    (60, 220, 220),
+    (255, 150, 60),
+    (240, 240, 240),
+    (150, 230, 90),
+];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Dash {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl Dash {
+    // (on, off) lengths in pixels, none for a solid line
+    pub fn pattern(self) -> Option<(f32, f32)> {
+        match self {
+            Dash::Solid => None,
+            Dash::Dashed => Some((10., 6.)),
+            Dash::Dotted => Some((1., 5.)),
+        }
+    }
+}
+
+// how an equation's curve is drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    pub color: Color,
+    pub width: f32,
+    pub dash: Dash,
+}
+
+impl Style {
+    // the nth equation's style, the colors repeat with a different dash once they run out
+    pub fn from_palette(index: usize) -> Self {
+        let (r, g, b) = PALETTE[index % PALETTE.len()];
+        let dash = match index / PALETTE.len() % 3 {
+            0 => Dash::Solid,
+            1 => Dash::Dashed,
+            _ => Dash::Dotted,
+        };
+
+        Style {
+            color: Color::rgb(r, g, b),
+            width: 2.,
+            dash,
+        }
+    }
+
+    // wider, for the equation under the cursor
+    pub fn highlighted(self) -> Self {
+        Style {
+            width: self.width + 2.,
+            ..self
+        }
+    }
+
+    // round caps so the dots of a dotted line are dots
+    pub fn paint(&self) -> Paint {
+        Paint::color(self.color)
+            .with_line_width(self.width)
+            .with_line_cap(LineCap::Round)
+    }
+}
+
+// surfaces don't dash strokes, so the strips are cut into the pieces that are drawn
+// the pattern carries on from one segment to the next so the dashes are even along curves
+pub fn dash_strips(strips: &[Vec<(f32, f32)>], pattern: (f32, f32)) -> Vec<Vec<(f32, f32)>> {
+    let (on_length, off_length) = pattern;
+    let mut dashes = vec![];
+
+    for strip in strips.iter().filter(|strip| !strip.is_empty()) {
+        let mut on = true;
+        let mut left = on_length;
+        let mut dash = vec![strip[0]];
+
+        for pair in strip.windows(2) {
+            let (mut from, to) = (pair[0], pair[1]);
+            let mut length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+
+            // the current dash or gap ends partway along this segment
+            while length > left {
+                let t = left / length;
+                let point = (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+                if on {
+                    dash.push(point);
+                    dashes.push(std::mem::take(&mut dash));
+                } else {
+                    dash = vec![point];
+                }
+
+                on = !on;
+                from = point;
+                length -= left;
+                left = if on { on_length } else { off_length };
+            }
+
+            left -= length;
+            if on {
+                dash.push(to);
+            }
+        }
+
+        if on && dash.len() > 1 {
+            dashes.push(dash);
+        }
+    }
+
+    dashes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dashes_follow_the_pattern() {
+        let line = vec![vec![(0., 0.), (30., 0.)]];
+        assert_eq!(
+            dash_strips(&line, (10., 5.)),
+            vec![vec![(0., 0.), (10., 0.)], vec![(15., 0.), (25., 0.)]]
+        );
+
+        // a dash can bend around the corner between segments
+        let corner = vec![vec![(0., 0.), (6., 0.), (6., 20.)]];
+        let dashes = dash_strips(&corner, (10., 5.));
+        assert_eq!(dashes[0], vec![(0., 0.), (6., 0.), (6., 4.)]);
+        assert_eq!(dashes[1], vec![(6., 9.), (6., 19.)]);
+        assert_eq!(dashes.len(), 2);
+    }
+
+    #[test]
+    fn palette_repeats_with_a_new_dash() {
+        assert_eq!(Style::from_palette(0).dash, Dash::Solid);
+        assert_ne!(Style::from_palette(0).color, Style::from_palette(1).color);
+
+        let repeated = Style::from_palette(PALETTE.len());
+        assert_eq!(repeated.color, Style::from_palette(0).color);
+        assert_eq!(repeated.dash, Dash::Dashed);
+    }
+}
@@ -1,11 +1,14 @@
+use application::cli::{self, Args, Command};
+use application::scene::{self, Overlays, View, DEFAULT_SIZE};
 use application::{femtovg_init, parser};
-use grapher::equation::{Polynomial, PolynomialBuilder, Term};
+use grapher::equation::{Equation, Polynomial, PolynomialBuilder, Term};
+use grapher::expression::{Expression, Function};
 use winit::event_loop::EventLoop;
 
 mod application;
 mod grapher;
 
-fn demo_equations() -> Vec<Polynomial> {
+fn demo_equations() -> Vec<Equation> {
     let linear = PolynomialBuilder::new()
         .plus_x_times(0.5)
         .plus_const(1.)
@@ -22,12 +25,11 @@ fn demo_equations() -> Vec<Polynomial> {
         .plus_const(-1.)
         .build();
 
-    let cubic: Polynomial = PolynomialBuilder::new()
-        .plus_x_cubed_times(0.01)
-        .plus_x_squared_times(-0.2)
-        .plus_x_times(1.)
-        .plus_const(0.)
-        .build();
+    // 0.01x(x - 10)^2
+    let cubic: Polynomial = (Polynomial::constant(0.01)
+        * Polynomial::x()
+        * (Polynomial::x() - Polynomial::constant(10.)).pow(2))
+    .simplify();
 
     let _poly: Polynomial = PolynomialBuilder::new()
         .add_term(Term::x_to_the(6).times(0.5))
@@ -45,7 +47,18 @@ fn demo_equations() -> Vec<Polynomial> {
         .plus_const(-99.8)
         .build();
 
-    vec![linear, quad, neg_quad, cubic, s]
+    // 2sin(x) + 0.05x^2
+    let wave = Expression::constant(2.) * Expression::call(Function::Sin, Expression::x())
+        + Expression::constant(0.05) * Expression::x().pow(Expression::constant(2.));
+
+    vec![
+        linear.into(),
+        quad.into(),
+        neg_quad.into(),
+        cubic.into(),
+        s.into(),
+        wave.into(),
+    ]
 }
 
 fn is_wasm() -> bool {
@@ -62,20 +75,81 @@ fn is_wasm() -> bool {
 }
 
 fn main() {
-    let equations = if is_wasm() || parser::has_demo_arg() {
-        demo_equations()
-    } else {
-        parser::get_input()
+    if is_wasm() {
+        graph_in_window(View::new(DEFAULT_SIZE), demo_equations());
+        return;
+    }
+
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => exit_with(&error),
     };
+    if args.help {
+        print!("{}", cli::USAGE);
+        return;
+    }
 
-    println!("Graphing equations:");
-    for e in &equations {
-        println!("{e}");
+    let mut equations = args
+        .read_equations()
+        .unwrap_or_else(|error| exit_with(&error));
+    if equations.is_empty() {
+        equations = parser::get_input();
     }
+    if equations.is_empty() {
+        exit_with("No equations were given");
+    }
+
+    match args.command {
+        Command::Eval => print!("{}", cli::eval_table(&equations, &args.at)),
+        Command::Roots => print!("{}", cli::roots_table(&equations, args.view)),
+        Command::Graph | Command::Render => {
+            println!("Graphing equations:");
+            for e in &equations {
+                println!("{e}");
+            }
+
+            if args.opens_window() {
+                graph_in_window(args.view, equations);
+            } else {
+                save_images(&args, equations);
+            }
+        }
+    }
+}
 
+fn graph_in_window(view: View, equations: Vec<Equation>) {
     let event_loop = EventLoop::new().expect("failed to create event loop");
 
-    let mut app = femtovg_init::init_canvas(&event_loop, equations);
+    let mut app = femtovg_init::init_canvas(&event_loop, view, equations);
 
     event_loop.run_app(&mut app).expect("run failed");
 }
+
+// headless, the graph goes straight to the files
+fn save_images(args: &Args, equations: Vec<Equation>) {
+    let plots = scene::plots(equations);
+
+    if let Some(file) = &args.svg {
+        let svg = scene::to_svg(args.view, &plots, Overlays::default());
+        save(file, svg.as_bytes());
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(file) = &args.png {
+        match scene::to_png(args.view, &plots, Overlays::default()) {
+            Ok(png) => save(file, &png),
+            Err(error) => exit_with(&format!("Couldn't draw {file}: {error}")),
+        }
+    }
+}
+
+fn save(file: &str, contents: &[u8]) {
+    match std::fs::write(file, contents) {
+        Ok(()) => println!("Saved {file}"),
+        Err(error) => exit_with(&format!("Couldn't save {file}: {error}")),
+    }
+}
+
+fn exit_with(error: &str) -> ! {
+    eprintln!("{error}");
+    std::process::exit(1);
+}